@@ -50,6 +50,8 @@ pub struct Body {
 
 impl Arena for Body {
     type Allocator = FixedAllocator<Self>;
+    type Layout = Layout64;
+    const NAME: &'static str = "Body";
 }
 
 impl Body {
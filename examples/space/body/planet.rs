@@ -1,37 +1,43 @@
 use super::*;
 
-#[derive(Debug, Clone)]
-pub struct Planet {
-    pub body: BodyRow,
-    pub moons: Vec<BodyRow>,
-}
+prefab! {
+    pub struct Planet {
+        type State = State;
+        type Parent = Id<System>;
+        type Ids = struct PlanetIds;
 
-impl State {
-    pub fn create_planet(&mut self, planet: Planet, system: Id<System>) -> PlanetIds {
-        let links = BodyLinks {
-            system,
-            orbit: Orbit::Planet,
+        body: BodyRow => Id<Body> = |state, body, system| {
+            let links = BodyLinks {
+                system,
+                orbit: Orbit::Planet,
+            };
+            state.body.create(body, links)
         };
-        let body = self.body.create(planet.body, links);
 
-        let moons = planet
-            .moons
-            .into_iter()
-            .map(|moon| {
-                let links = BodyLinks {
-                    system,
-                    orbit: Orbit::Moon { parent: body },
-                };
-                self.body.create(moon, links)
-            })
-            .collect();
+        children {
+            moons: Moon,
+        }
+    }
+}
+
+prefab! {
+    pub struct Moon {
+        type State = State;
+        type Parent = Id<Body>;
+        type Ids = struct MoonIds;
 
-        PlanetIds { body, moons }
+        body: BodyRow => Id<Body> = |state, body, planet| {
+            let links = BodyLinks {
+                system: *state.body.system.get(planet),
+                orbit: Orbit::Moon { parent: planet },
+            };
+            state.body.create(body, links)
+        };
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct PlanetIds {
-    pub body: Id<Body>,
-    pub moons: Vec<Id<Body>>,
+impl State {
+    pub fn create_planet(&mut self, planet: Planet, system: Id<System>) -> PlanetIds {
+        planet.spawn(self, system)
+    }
 }
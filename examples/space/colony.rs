@@ -6,15 +6,45 @@ pub struct Colony {
 
     pub body: Component<Self, Id<Body>>,
     pub name: Component<Self, String>,
-    pub population: Component<Self, f64>,
-    pub food_stockpile: Component<Self, f64>,
-    pub food_production: Component<Self, f64>,
-    pub food_supply_demand: Component<Self, f64>,
+    pub population: ChunkedComponent<Self, f64, 8>,
+    pub food_stockpile: ChunkedComponent<Self, f64, 8>,
+    pub food_production: ChunkedComponent<Self, f64, 8>,
+    pub food_supply_demand: ChunkedComponent<Self, f64, 8>,
     pub government: Component<Self, Id<Government>>,
 }
 
 impl Arena for Colony {
     type Allocator = DynamicAllocator<Self>;
+    type Layout = Layout64;
+    const NAME: &'static str = "Colony";
+}
+
+state_hash! {
+    Colony {
+        alloc,
+        body,
+        name,
+        population,
+        food_stockpile,
+        food_production,
+        food_supply_demand,
+        government,
+    }
+}
+
+clone_entity! {
+    Colony {
+        alloc: alloc,
+        components: [
+            name,
+            population,
+            food_stockpile,
+            food_production,
+            food_supply_demand,
+        ],
+        links: [body, government],
+        rows: [],
+    }
 }
 
 impl Colony {
@@ -35,6 +65,16 @@ impl Colony {
     }
 }
 
+impl Reflect for Colony {
+    fn reflect<'a>(&'a self, fields: &mut Fields<'a, Self>) {
+        fields
+            .component("name", &self.name)
+            .chunked("population", &self.population)
+            .chunked("food_stockpile", &self.food_stockpile)
+            .component("government", &self.government);
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ColonyRow {
     pub name: String,
@@ -49,14 +89,13 @@ pub struct ColonyLinks {
 
 impl Colony {
     pub fn update_food(&mut self) {
-        self.food_stockpile
-            .iter_mut()
-            .zip(&mut self.food_supply_demand)
-            .zip(&self.food_production)
-            .zip(&self.population)
-            .for_each(|(((stocks, supply_demand), production), pop)| {
-                *supply_demand = production - 2.0 * pop;
-                *stocks -= *supply_demand;
+        self.food_supply_demand
+            .zip_lanes(&self.food_production, |demand, production| {
+                *demand = production
             });
+        self.food_supply_demand
+            .zip_lanes(&self.population, |demand, pop| *demand -= 2.0 * pop);
+
+        self.food_stockpile -= &self.food_supply_demand;
     }
 }
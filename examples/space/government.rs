@@ -12,6 +12,27 @@ pub struct Government {
 
 impl Arena for Government {
     type Allocator = DynamicAllocator<Self>;
+    type Layout = Layout64;
+    const NAME: &'static str = "Government";
+}
+
+state_hash! {
+    Government {
+        alloc,
+        name,
+        capital,
+        trade,
+    }
+}
+
+impl HasAllocator for Government {
+    fn allocator(&self) -> &Allocator<Self> {
+        &self.alloc
+    }
+
+    fn allocator_mut(&mut self) -> &mut Allocator<Self> {
+        &mut self.alloc
+    }
 }
 
 impl Government {
@@ -20,7 +41,7 @@ impl Government {
 
         self.name.insert(id, govt.name);
 
-        id.value
+        id.id()
     }
 
     pub fn build_trade_graphs(&mut self, colonies: &Colony, bodies: &Body) {
@@ -37,15 +37,17 @@ fn main() {
                 offset: 0.0,
             },
         },
-        moons: vec![BodyRow {
-            name: "Luna".to_string(),
-            mass: 7.348e22,
-            radius: 1737.1e3,
-            albedo: 0.12,
-            orbit: OrbitParams {
-                period: 27.322 * 24.0 * 60.0 * 60.0,
-                radius: 3.48e8,
-                offset: 0.0,
+        moons: vec![Moon {
+            body: BodyRow {
+                name: "Luna".to_string(),
+                mass: 7.348e22,
+                radius: 1737.1e3,
+                albedo: 0.12,
+                orbit: OrbitParams {
+                    period: 27.322 * 24.0 * 60.0 * 60.0,
+                    radius: 3.48e8,
+                    offset: 0.0,
+                },
             },
         }],
     };
@@ -89,10 +91,13 @@ fn main() {
     let _china = state.colony.create(china, links);
 
     state.print_with_government();
+    state.print_colonies();
 
     let time = 3600.0;
 
     state.body.update_positions(time);
+
+    println!("State digest: {:016x}", state.digest().combined());
 }
 
 #[test]
@@ -20,10 +20,22 @@ impl State {
             .alloc
             .filter_living(iter)
             .for_each(|((colony, pop), govt_id)| {
-                if let Some(govt_id) = self.government.alloc.validate(*govt_id) {
-                    let govt = self.government.name.get(govt_id);
+                if let Some(govt) = self.government.entity(*govt_id) {
+                    let govt = govt.get(&self.government.name);
                     println!("{} ({}): {}", colony, govt, pop);
                 }
             });
     }
+
+    /// Digests of the arenas that are synchronized between peers.
+    pub fn digest(&self) -> StateDigest {
+        let mut digest = StateDigest::new();
+        digest.add(&self.colony).add(&self.government);
+        digest
+    }
+
+    pub fn print_colonies(&self) {
+        let inspector = Inspector::new(&self.colony, &self.colony.alloc);
+        println!("{}", inspector.table(self.colony.alloc.ids()));
+    }
 }
@@ -12,6 +12,8 @@ pub struct System {
 
 impl Arena for System {
     type Allocator = FixedAllocator<Self>;
+    type Layout = Layout64;
+    const NAME: &'static str = "System";
 }
 
 impl System {
@@ -1,9 +1,9 @@
-use crate::Id;
+use crate::{Arena, Id};
 use std::cmp::Ordering;
 use std::marker::PhantomData;
 
 #[derive(Debug)]
-pub enum GenerationCmp<ARENA> {
+pub enum GenerationCmp<ARENA: Arena> {
     Valid,
     OffByOne(Id<ARENA>),
     Outdated,
@@ -26,6 +26,10 @@ impl<ARENA> AllocGen<ARENA> {
     pub(crate) fn increment(&mut self) {
         self.gen += 1;
     }
+
+    pub(crate) fn get(&self) -> u64 {
+        self.gen
+    }
 }
 
 impl<ARENA> std::ops::Sub for AllocGen<ARENA> {
@@ -1,30 +1,88 @@
 use crate::allocator::alloc_gen::GenerationCmp;
+use crate::allocator::free_list::FreeList;
 use crate::*;
 use bit_vec::BitVec;
 use std::iter::Zip;
 use std::marker::PhantomData;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// What a `DynamicAllocator` does when killing an id would overflow the generation of its slot.
+///
+/// Chosen per arena with `Arena::OVERFLOW`, which defaults to `Retire`. Allocators used to wrap the
+/// generation silently, so arenas that relied on slots being reused indefinitely should opt back in
+/// with `dynamic_arena!(Colony, overflow = GenOverflow::Wrap)`.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum GenOverflow {
+    /// The slot is never reused, so an old id can never be mistaken for a living one.
+    #[default]
+    Retire,
+    /// The slot is reused from the first generation. Ids that were killed before the wrap can
+    /// compare equal to new ids. Each wrap is counted, see [`DynamicAllocator::wraps`].
+    Wrap,
+}
 
 // #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug)]
-pub struct DynamicAllocator<ARENA> {
+pub struct DynamicAllocator<ARENA: Arena> {
     current_gen: Vec<Id<ARENA>>,
-    dead: Vec<u32>,
+    dead: FreeList,
     living: BitVec,
     generation: AllocGen<ARENA>,
     last_killed: Option<Id<ARENA>>,
+    retired: usize,
+    wraps: u64,
+    creates: u64,
+    /// The number of ids handed out by `reserve` since the last `flush`.
+    reserved: AtomicUsize,
+}
+
+/// A snapshot of the health of a `DynamicAllocator`, see [`DynamicAllocator::stats`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AllocatorStats {
+    /// The number of slots that can be allocated before the allocator reallocates.
+    pub capacity: usize,
+    /// The number of slots that have been allocated, living, dead or retired.
+    pub slots: usize,
+    pub live: usize,
+    /// The number of slots in the free list, waiting to be reused.
+    pub dead: usize,
+    pub retired: usize,
+    /// The number of times a slot's generation has wrapped, see [`GenOverflow::Wrap`].
+    pub wraps: u64,
+    /// The fraction of slots that are not alive, between 0 and 1.
+    pub fragmentation: f64,
+    /// The highest generation of any living id, or 0 if none are alive.
+    pub max_generation: u64,
+    pub kills: u64,
+    pub creates: u64,
 }
 
-impl<ARENA> DynamicAllocator<ARENA> {
+impl<ARENA: Arena> DynamicAllocator<ARENA> {
     pub fn with_capacity(capacity: usize) -> Self {
         Self {
             current_gen: Vec::with_capacity(capacity),
-            dead: Vec::default(),
+            dead: FreeList::new(ARENA::REUSE),
             living: BitVec::with_capacity(capacity),
             generation: AllocGen::default(),
             last_killed: None,
+            retired: 0,
+            wraps: 0,
+            creates: 0,
+            reserved: AtomicUsize::new(0),
         }
     }
 
+    /// The number of slots that have been permanently retired by [`GenOverflow::Retire`].
+    pub fn retired(&self) -> usize {
+        self.retired
+    }
+
+    /// The number of times a slot's generation has wrapped under [`GenOverflow::Wrap`]. Poll it
+    /// to detect that old ids may have become indistinguishable from living ones.
+    pub fn wraps(&self) -> u64 {
+        self.wraps
+    }
+
     pub fn validate(&self, id: Id<ARENA>) -> Option<Valid<Id<ARENA>>> {
         if self.is_alive(id) {
             Some(Valid::new(id))
@@ -33,25 +91,132 @@ impl<ARENA> DynamicAllocator<ARENA> {
         }
     }
 
+    /// Calls `f` with a copyable token that validates ids against this allocator.
+    ///
+    /// The token's lifetime is unique to this call, so ids validated through it cannot leave the
+    /// closure, and tokens of two different allocators cannot be used interchangeably.
+    pub fn with_token<R, F>(&self, f: F) -> R
+    where
+        F: for<'id> FnOnce(ValidToken<'id, ARENA>) -> R,
+    {
+        f(ValidToken::new(self))
+    }
+
+    /// Unpacks an id from external bits (see [`Id::to_bits`]) if it is alive in this allocator.
+    pub fn validate_bits(&self, bits: u64) -> Option<Valid<'_, Id<ARENA>>> {
+        Id::from_u64(bits).and_then(|id| self.validate(id))
+    }
+
+    /// The number of living entities.
+    pub fn len(&self) -> usize {
+        self.slot_count() - self.dead.len() - self.retired
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of slots that can be allocated without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.current_gen.capacity()
+    }
+
+    /// The number of slots that have been allocated, living, dead or retired. A component that has
+    /// a value for every id has this length.
+    pub fn slot_count(&self) -> usize {
+        self.current_gen.len()
+    }
+
+    pub fn stats(&self) -> AllocatorStats {
+        let slots = self.slot_count();
+        let live = self.len();
+
+        let fragmentation = if slots == 0 {
+            0.0
+        } else {
+            (slots - live) as f64 / slots as f64
+        };
+
+        let max_generation = self
+            .current_gen
+            .iter()
+            .zip(self.living.iter())
+            .filter(|(_, alive)| *alive)
+            .map(|(id, _)| id.gen().get_bits())
+            .max()
+            .unwrap_or(0);
+
+        AllocatorStats {
+            capacity: self.capacity(),
+            slots,
+            live,
+            dead: self.dead.len(),
+            retired: self.retired,
+            wraps: self.wraps,
+            fragmentation,
+            max_generation,
+            kills: self.generation.get(),
+            creates: self.creates,
+        }
+    }
+
     pub fn generation(&self) -> AllocGen<ARENA> {
         self.generation
     }
 }
 
-impl<ARENA> Default for DynamicAllocator<ARENA> {
+impl<ARENA: Arena> Validate<ARENA> for DynamicAllocator<ARENA> {
+    fn validate(&self, id: Id<ARENA>) -> Option<Valid<'_, Id<ARENA>>> {
+        DynamicAllocator::validate(self, id)
+    }
+}
+
+impl<ARENA: Arena> MemoryUsage for DynamicAllocator<ARENA> {
+    fn memory_usage(&self) -> usize {
+        self.current_gen.capacity() * std::mem::size_of::<Id<ARENA>>()
+            + self.dead.memory_usage()
+            + self.living.capacity() / 8
+    }
+}
+
+/// Hashes the generation of every slot, the free list and the allocator's generation, so that two
+/// allocators with the same living ids but a different history have different digests. Counters
+/// kept for `stats` and ids reserved since the last `flush` are not hashed.
+impl<ARENA: Arena> StateHash for DynamicAllocator<ARENA> {
+    fn state_hash(&self, hasher: &mut StateHasher) {
+        self.current_gen.state_hash(hasher);
+        self.dead.state_hash(hasher);
+        self.living.state_hash(hasher);
+        hasher.write_u64(self.generation.get());
+        self.last_killed.state_hash(hasher);
+    }
+}
+
+impl<ARENA: Arena> Default for DynamicAllocator<ARENA> {
     fn default() -> Self {
         Self {
             current_gen: vec![],
-            dead: vec![],
+            dead: FreeList::new(ARENA::REUSE),
             living: Default::default(),
             generation: Default::default(),
             last_killed: None,
+            retired: 0,
+            wraps: 0,
+            creates: 0,
+            reserved: AtomicUsize::new(0),
         }
     }
 }
 
-impl<ARENA> DynamicAllocator<ARENA> {
+impl<ARENA: Arena> DynamicAllocator<ARENA> {
     pub fn create(&mut self) -> Valid<Id<ARENA>> {
+        self.flush();
+        self.create_unflushed()
+    }
+
+    fn create_unflushed(&mut self) -> Valid<'_, Id<ARENA>> {
+        self.creates += 1;
+
         let id = if let Some(index) = self.dead.pop() {
             self.reuse_index(index)
         } else {
@@ -61,16 +226,14 @@ impl<ARENA> DynamicAllocator<ARENA> {
         Valid::new(id)
     }
 
-    fn reuse_index(&mut self, index: u32) -> Id<ARENA> {
-        let i = index as usize;
+    fn reuse_index(&mut self, index: usize) -> Id<ARENA> {
+        self.living.set(index, true);
 
-        self.living.set(i, true);
-
-        self.current_gen[i]
+        self.current_gen[index]
     }
 
     fn create_new(&mut self) -> Id<ARENA> {
-        let index = self.current_gen.len() as u32;
+        let index = self.current_gen.len();
 
         let id = Id::first(index);
 
@@ -80,7 +243,44 @@ impl<ARENA> DynamicAllocator<ARENA> {
         id
     }
 
+    /// Reserves an id without exclusive access, so that entities can be spawned from parallel
+    /// systems. Ids are handed out in the same order as `create`.
+    ///
+    /// The id is not alive until the next call to `flush`, `create` or `kill`, any of which
+    /// materializes all reserved ids.
+    ///
+    /// Returns `None` if the arena's layout has no index left for another entity.
+    pub fn reserve(&self) -> Option<Id<ARENA>> {
+        let n = self
+            .reserved
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| {
+                self.reserved_id(n).map(|_| n + 1)
+            })
+            .ok()?;
+
+        self.reserved_id(n)
+    }
+
+    /// The id that the `n`th call to `reserve` since the last `flush` hands out.
+    fn reserved_id(&self, n: usize) -> Option<Id<ARENA>> {
+        match self.dead.nth(n) {
+            Some(index) => Some(self.current_gen[index]),
+            None => Id::try_first(self.slot_count() + n - self.dead.len()),
+        }
+    }
+
+    /// Materializes the ids handed out by `reserve`.
+    pub fn flush(&mut self) {
+        let reserved = std::mem::take(self.reserved.get_mut());
+
+        for _ in 0..reserved {
+            self.create_unflushed();
+        }
+    }
+
     pub fn kill(&mut self, id: Id<ARENA>) -> bool {
+        self.flush();
+
         if self.is_alive(id) {
             self.kill_unchecked(id);
             true
@@ -92,19 +292,38 @@ impl<ARENA> DynamicAllocator<ARENA> {
     fn kill_unchecked(&mut self, id: Id<ARENA>) {
         let index = id.index_usize();
 
-        if let Some(current_id) = self.current_gen.get_mut(index) {
-            current_id.increment();
+        if let Some(next) = id.next_gen() {
+            self.current_gen[index] = next;
+            self.dead.push(index);
+        } else {
+            self.overflow_gen(id);
         }
 
-        self.dead.push(id.index_u32());
         self.living.set(index, false);
         self.generation.increment();
         self.last_killed = Some(id);
     }
 
+    fn overflow_gen(&mut self, id: Id<ARENA>) {
+        let index = id.index_usize();
+
+        match ARENA::OVERFLOW {
+            GenOverflow::Retire => {
+                self.retired += 1;
+                return;
+            }
+            GenOverflow::Wrap => self.wraps += 1,
+        }
+
+        self.current_gen[index] = id.first_gen();
+        self.dead.push(index);
+    }
+
     pub fn is_alive(&self, id: Id<ARENA>) -> bool {
-        if let Some(current) = self.current_gen.get(id.index_usize()) {
-            id.eq(current)
+        let index = id.index_usize();
+
+        if let Some(current) = self.current_gen.get(index) {
+            id.eq(current) && self.living[index]
         } else {
             false
         }
@@ -160,7 +379,7 @@ pub struct Living<'a, ID> {
     marker: PhantomData<ID>,
 }
 
-impl<'a, ID> Living<'a, ID> {
+impl<'a, ID: Arena> Living<'a, ID> {
     fn new(alloc: &'a DynamicAllocator<ID>) -> Self {
         Self {
             bits: alloc.living.iter(),
@@ -169,24 +388,29 @@ impl<'a, ID> Living<'a, ID> {
     }
 }
 
-impl<'a, ID> IntoIterator for Living<'a, ID> {
+impl<ID> Iterator for Living<'_, ID> {
     type Item = bool;
-    type IntoIter = bit_vec::Iter<'a>;
 
-    fn into_iter(self) -> Self::IntoIter {
-        self.bits
+    fn next(&mut self) -> Option<Self::Item> {
+        self.bits.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.bits.size_hint()
     }
 }
 
+impl<ID> ExactSizeIterator for Living<'_, ID> {}
+
 impl<'a, ID> ContextualIterator for Living<'a, ID> {
     type Context = ID;
 }
 
-pub struct Ids<'a, ID> {
+pub struct Ids<'a, ID: Arena> {
     iter: Zip<std::slice::Iter<'a, Id<ID>>, bit_vec::Iter<'a>>,
 }
 
-impl<'a, ID> Ids<'a, ID> {
+impl<'a, ID: Arena> Ids<'a, ID> {
     fn new(alloc: &'a DynamicAllocator<ID>) -> Self {
         Self {
             iter: alloc.current_gen.iter().zip(alloc.living.iter()),
@@ -194,7 +418,7 @@ impl<'a, ID> Ids<'a, ID> {
     }
 }
 
-impl<'a, ID> Iterator for Ids<'a, ID> {
+impl<'a, ID: Arena> Iterator for Ids<'a, ID> {
     type Item = Option<Valid<'a, &'a Id<ID>>>;
     fn next(&mut self) -> Option<Self::Item> {
         self.iter.next().map(
@@ -207,9 +431,16 @@ impl<'a, ID> Iterator for Ids<'a, ID> {
             },
         )
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
 }
 
-impl<'a, ID> ContextualIterator for Ids<'a, ID> {
+/// Yields one item per slot, so the length is the allocator's `slot_count`.
+impl<ID: Arena> ExactSizeIterator for Ids<'_, ID> {}
+
+impl<'a, ID: Arena> ContextualIterator for Ids<'a, ID> {
     type Context = ID;
 }
 
@@ -233,7 +464,7 @@ mod tests {
         let id1 = gen_allocator.create().value;
         gen_allocator.kill(id1);
 
-        let reused_id = Id::first(0).next_gen();
+        let reused_id = Id::first(0).next_gen().unwrap();
         assert_eq!(reused_id, gen_allocator.create().value);
         assert_eq!(Id::first(1), gen_allocator.create().value);
     }
@@ -257,11 +488,302 @@ mod tests {
         assert!(allocator.validate(id).is_none());
     }
 
+    #[test]
+    fn validate_bits() {
+        let mut allocator = Allocator::<GenerationalArena>::default();
+
+        let id = allocator.create().value;
+        let bits = id.to_bits();
+
+        assert_eq!(Some(id), allocator.validate_bits(bits).map(|id| id.value));
+        assert!(allocator.validate_bits(0).is_none());
+
+        allocator.kill(id);
+
+        assert!(allocator.validate_bits(bits).is_none());
+    }
+
+    #[test]
+    fn stats() {
+        let mut allocator = Allocator::<GenerationalArena>::with_capacity(8);
+
+        let a = allocator.create().value;
+        let b = allocator.create().value;
+        allocator.create();
+        allocator.create();
+        allocator.kill(a);
+        allocator.kill(b);
+        allocator.create();
+
+        let stats = allocator.stats();
+
+        assert_eq!(8, stats.capacity);
+        assert_eq!(4, stats.slots);
+        assert_eq!(3, stats.live);
+        assert_eq!(1, stats.dead);
+        assert_eq!(0.25, stats.fragmentation);
+        assert_eq!(2, stats.max_generation);
+        assert_eq!(2, stats.kills);
+        assert_eq!(5, stats.creates);
+    }
+
+    #[test]
+    fn stats_max_generation_ignores_dead_slots() {
+        let mut allocator = Allocator::<GenerationalArena>::default();
+
+        let a = allocator.create().value;
+        allocator.create();
+        allocator.kill(a);
+
+        assert_eq!(1, allocator.stats().max_generation);
+    }
+
+    #[test]
+    fn len() {
+        let mut allocator = Allocator::<GenerationalArena>::with_capacity(4);
+        assert!(allocator.is_empty());
+
+        let a = allocator.create().value;
+        allocator.create();
+        allocator.create();
+        allocator.kill(a);
+
+        assert_eq!(2, allocator.len());
+        assert_eq!(3, allocator.slot_count());
+        assert!(allocator.capacity() >= 4);
+        assert!(!allocator.is_empty());
+    }
+
+    #[test]
+    fn exact_size_iterators() {
+        let mut allocator = Allocator::<GenerationalArena>::default();
+
+        let a = allocator.create().value;
+        allocator.create();
+        allocator.kill(a);
+
+        let mut ids = allocator.ids();
+        assert_eq!(2, ids.len());
+        ids.next();
+        assert_eq!(1, ids.len());
+
+        assert_eq!(2, allocator.living().len());
+    }
+
+    #[test]
+    fn memory_usage() {
+        let allocator = Allocator::<GenerationalArena>::with_capacity(8);
+
+        assert!(allocator.memory_usage() >= 8 * std::mem::size_of::<Id<GenerationalArena>>());
+    }
+
+    fn reuse_order<A: Arena<Allocator = DynamicAllocator<A>>>() -> Vec<usize> {
+        let mut allocator = Allocator::<A>::default();
+
+        let ids: Vec<_> = (0..4).map(|_| allocator.create().value).collect();
+        for i in [2, 0, 3].iter() {
+            allocator.kill(ids[*i]);
+        }
+
+        (0..3).map(|_| allocator.create().index()).collect()
+    }
+
+    struct FifoArena;
+
+    dynamic_arena!(FifoArena, reuse = Reuse::Fifo);
+
+    struct LowestArena;
+
+    dynamic_arena!(LowestArena, Layout64, reuse = Reuse::Lowest);
+
+    #[test]
+    fn reuse_policies() {
+        assert_eq!(vec![3, 0, 2], reuse_order::<GenerationalArena>());
+        assert_eq!(vec![2, 0, 3], reuse_order::<FifoArena>());
+        assert_eq!(vec![0, 2, 3], reuse_order::<LowestArena>());
+    }
+
+    #[test]
+    fn reserve_then_flush() {
+        let mut allocator = Allocator::<GenerationalArena>::default();
+
+        let a = allocator.create().value;
+        allocator.create();
+        allocator.kill(a);
+
+        let reused = allocator.reserve().unwrap();
+        let fresh = allocator.reserve().unwrap();
+
+        assert_eq!(a.next_gen(), Some(reused));
+        assert_eq!(Id::first(2), fresh);
+        assert!(!allocator.is_alive(reused));
+        assert!(!allocator.is_alive(fresh));
+
+        allocator.flush();
+
+        assert!(allocator.is_alive(reused));
+        assert!(allocator.is_alive(fresh));
+        assert_eq!(3, allocator.len());
+    }
+
+    #[test]
+    fn create_flushes_reserved_ids() {
+        let mut allocator = Allocator::<GenerationalArena>::default();
+
+        let reserved = allocator.reserve().unwrap();
+        let created = allocator.create().value;
+
+        assert!(allocator.is_alive(reserved));
+        assert_eq!(Id::first(1), created);
+    }
+
+    #[test]
+    fn reserve_from_threads() {
+        let mut allocator = Allocator::<LowestArena>::default();
+
+        let ids: Vec<_> = (0..8).map(|_| allocator.create().value).collect();
+        for id in ids.iter().step_by(2) {
+            allocator.kill(*id);
+        }
+
+        let allocator_ref = &allocator;
+        let mut reserved: Vec<Id<LowestArena>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..4)
+                .map(|_| {
+                    scope.spawn(move || {
+                        (0..4)
+                            .map(|_| allocator_ref.reserve().unwrap())
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap())
+                .collect()
+        });
+
+        allocator.flush();
+
+        reserved.sort();
+        reserved.dedup();
+        assert_eq!(16, reserved.len());
+        assert!(reserved.iter().all(|id| allocator.is_alive(*id)));
+        assert_eq!(20, allocator.len());
+    }
+
+    #[derive(Debug)]
+    struct FourSlotArena;
+
+    dynamic_arena!(FourSlotArena, Layout<std::num::NonZeroU32, 30>);
+
+    #[test]
+    fn reserve_fails_when_the_layout_is_full() {
+        let mut allocator = Allocator::<FourSlotArena>::default();
+        let id = allocator.create().value;
+        allocator.create();
+        allocator.create();
+        allocator.kill(id);
+
+        assert!(allocator.reserve().is_some());
+        assert_eq!(Some(Id::first(3)), allocator.reserve());
+        assert_eq!(None, allocator.reserve());
+
+        allocator.flush();
+        assert_eq!(4, allocator.len());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn reserve_from_rayon() {
+        let mut allocator = Allocator::<GenerationalArena>::default();
+
+        let reserved: Vec<_> = (0..100)
+            .into_par_iter()
+            .map(|_| allocator.reserve().unwrap())
+            .collect();
+
+        allocator.flush();
+
+        assert_eq!(100, allocator.len());
+        assert!(reserved.iter().all(|id| allocator.is_alive(*id)));
+    }
+
+    #[derive(Debug)]
+    struct TwoBitArena;
+
+    dynamic_arena!(TwoBitArena, Layout<std::num::NonZeroU32, 2>);
+
+    #[derive(Debug)]
+    struct WrappingArena;
+
+    dynamic_arena!(
+        WrappingArena,
+        Layout<std::num::NonZeroU32, 2>,
+        overflow = GenOverflow::Wrap
+    );
+
+    /// Kills and recreates the first slot until its generation is exhausted.
+    fn exhaust_first_slot<A>(allocator: &mut Allocator<A>) -> Id<A>
+    where
+        A: Arena<Allocator = DynamicAllocator<A>>,
+    {
+        let first = allocator.create().value;
+        allocator.kill(first);
+
+        for _ in 0..2 {
+            let id = allocator.create().value;
+            assert_eq!(0, id.index_usize());
+            allocator.kill(id);
+        }
+
+        first
+    }
+
+    #[test]
+    fn retire_on_gen_overflow() {
+        let mut allocator = Allocator::<TwoBitArena>::default();
+
+        let first = exhaust_first_slot(&mut allocator);
+
+        let id = allocator.create().value;
+        assert_eq!(Id::first(1), id);
+        assert!(!allocator.is_alive(first));
+        assert_eq!(1, allocator.retired());
+        assert_eq!(1, allocator.stats().retired);
+        assert_eq!(0, allocator.stats().dead);
+    }
+
+    #[test]
+    fn retired_slot_is_not_alive() {
+        let mut allocator = Allocator::<TwoBitArena>::default();
+
+        exhaust_first_slot(&mut allocator);
+
+        let last = allocator.last_killed().unwrap();
+        assert!(!allocator.is_alive(last));
+        assert!(allocator.ids().all(|id| id.is_none()));
+    }
+
+    #[test]
+    fn wrap_on_gen_overflow_reuses_slot() {
+        let mut allocator = Allocator::<WrappingArena>::default();
+
+        let first = exhaust_first_slot(&mut allocator);
+
+        assert_eq!(first, allocator.create().value);
+        assert!(allocator.is_alive(first));
+        assert_eq!(0, allocator.retired());
+        assert_eq!(1, allocator.wraps());
+        assert_eq!(1, allocator.stats().wraps);
+    }
+
     #[test]
     fn gen_alloc_lifetime_test() {
         let mut allocator = Allocator::<GenerationalArena>::default();
 
-        let id1 = allocator.create().value; // Remove ".id" to cause a compiler error
+        let id1 = allocator.create().value; // keeping the `Valid` fails, see tests/compile_fail/valid_outlives_create.rs
         let id2 = allocator.create();
 
         println!("{:?}", id1);
@@ -1,57 +1,174 @@
 use crate::*;
+use bit_vec::BitVec;
 use std::marker::PhantomData;
 
+/// An allocator for entities that are never reused.
+///
+/// Entities can be retired, which tombstones them without the cost of generations: retired ids are
+/// skipped by `ids()` and fail `validate()`, but `Id<A>` remains a `ValidId` so that component
+/// access is unchecked.
 // #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug)]
 pub struct FixedAllocator<A> {
-    next_index: u32,
+    next_index: usize,
+    /// Empty until the first entity is retired.
+    retired: BitVec,
     marker: PhantomData<A>,
 }
 
-impl<A> FixedAllocator<A> {
+impl<A: Arena> FixedAllocator<A> {
     pub fn create(&mut self) -> Id<A> {
         let index = self.next_index;
         self.next_index += 1;
         Id::first(index)
     }
 
+    /// Ids of entities that have not been retired.
     pub fn ids(&self) -> Ids<A> {
         Ids::new(self)
     }
+
+    /// Whether each entity is alive, with one value per created entity.
+    pub fn living(&self) -> Living<'_, A> {
+        Living::new(self)
+    }
+
+    pub fn validate(&self, id: Id<A>) -> Option<Valid<'_, Id<A>>> {
+        if self.is_alive(id) {
+            Some(Valid::new(id))
+        } else {
+            None
+        }
+    }
+
+    /// Unpacks an id from external bits (see [`Id::to_bits`]) if it is alive in this allocator.
+    pub fn validate_bits(&self, bits: u64) -> Option<Valid<'_, Id<A>>> {
+        Id::from_u64(bits).and_then(|id| self.validate(id))
+    }
+
+    pub fn is_alive(&self, id: Id<A>) -> bool {
+        let index = id.index_usize();
+        index < self.next_index && !self.is_retired_index(index)
+    }
+
+    /// Permanently retires an entity. Returns false if the entity was not alive.
+    pub fn retire(&mut self, id: Id<A>) -> bool {
+        if !self.is_alive(id) {
+            return false;
+        }
+
+        let index = id.index_usize();
+        if self.retired.len() <= index {
+            self.retired.grow(index + 1 - self.retired.len(), false);
+        }
+        self.retired.set(index, true);
+
+        true
+    }
+
+    fn is_retired_index(&self, index: usize) -> bool {
+        self.retired.get(index).unwrap_or(false)
+    }
+}
+
+impl<A: Arena> Validate<A> for FixedAllocator<A> {
+    fn validate(&self, id: Id<A>) -> Option<Valid<'_, Id<A>>> {
+        FixedAllocator::validate(self, id)
+    }
 }
 
 impl<A> Default for FixedAllocator<A> {
     fn default() -> Self {
         Self {
             next_index: 0,
+            retired: BitVec::new(),
             marker: PhantomData,
         }
     }
 }
 
+impl<A> MemoryUsage for FixedAllocator<A> {
+    fn memory_usage(&self) -> usize {
+        self.retired.capacity() / 8
+    }
+}
+
+impl<A> StateHash for FixedAllocator<A> {
+    fn state_hash(&self, hasher: &mut StateHasher) {
+        hasher.write_usize(self.next_index);
+        self.retired.state_hash(hasher);
+    }
+}
+
+/// Ids of entities that have not been retired.
+///
+/// Retired ids are skipped, so zipping with a component only lines up in arenas that never retire.
+/// Zip with `living()` instead once entities may be retired.
 pub struct Ids<'a, ID> {
-    range: std::ops::Range<u32>,
-    marker: PhantomData<&'a ID>,
+    range: std::ops::Range<usize>,
+    retired: &'a BitVec,
+    marker: PhantomData<ID>,
 }
 
 impl<'a, ID> Ids<'a, ID> {
     pub fn new(alloc: &'a FixedAllocator<ID>) -> Self {
         Self {
             range: (0..alloc.next_index),
+            retired: &alloc.retired,
             marker: PhantomData,
         }
     }
 }
 
-impl<ID> Iterator for Ids<'_, ID> {
+impl<ID: Arena> Iterator for Ids<'_, ID> {
     type Item = Id<ID>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.range.next().map(Id::first)
+        let retired = self.retired;
+        self.range
+            .find(|index| !retired.get(*index).unwrap_or(false))
+            .map(Id::first)
     }
 }
 
-impl<ID> ContextualIterator for Ids<'_, ID> {
+impl<ID: Arena> ContextualIterator for Ids<'_, ID> {
+    type Context = ID;
+}
+
+pub struct Living<'a, ID> {
+    range: std::ops::Range<usize>,
+    retired: &'a BitVec,
+    marker: PhantomData<ID>,
+}
+
+impl<'a, ID> Living<'a, ID> {
+    fn new(alloc: &'a FixedAllocator<ID>) -> Self {
+        Self {
+            range: (0..alloc.next_index),
+            retired: &alloc.retired,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<ID> Iterator for Living<'_, ID> {
+    type Item = bool;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let retired = self.retired;
+        self.range
+            .next()
+            .map(|index| !retired.get(index).unwrap_or(false))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl<ID> ExactSizeIterator for Living<'_, ID> {}
+
+impl<ID> ContextualIterator for Living<'_, ID> {
     type Context = ID;
 }
 
@@ -67,4 +184,87 @@ mod test {
         assert_eq!(Id::first(0), fixed_allocator.create());
         assert_eq!(Id::first(1), fixed_allocator.create());
     }
+
+    #[test]
+    fn retire() {
+        let mut allocator = Allocator::<FixedArena>::default();
+
+        let a = allocator.create();
+        let b = allocator.create();
+        let c = allocator.create();
+
+        assert!(allocator.retire(b));
+        assert!(!allocator.retire(b));
+
+        assert!(allocator.validate(a).is_some());
+        assert!(allocator.validate(b).is_none());
+        assert_eq!(vec![a, c], allocator.ids().collect::<Vec<_>>());
+        assert_eq!(
+            vec![true, false, true],
+            allocator.living().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn validate_bits_checks_bounds() {
+        let mut allocator = Allocator::<FixedArena>::default();
+
+        let id = allocator.create();
+
+        assert_eq!(
+            Some(id),
+            allocator.validate_bits(id.to_bits()).map(|id| id.value)
+        );
+        assert!(allocator
+            .validate_bits(Id::<FixedArena>::first(1).to_bits())
+            .is_none());
+        assert!(allocator.validate_bits(0).is_none());
+    }
+
+    #[test]
+    fn retire_uncreated_id_returns_false() {
+        let mut allocator = Allocator::<FixedArena>::default();
+
+        assert!(!allocator.retire(Id::first(0)));
+        assert_eq!(0, allocator.memory_usage());
+    }
+
+    #[test]
+    fn ids_zip_with_component() {
+        let mut allocator = Allocator::<FixedArena>::default();
+        let mut values = Component::<FixedArena, u32>::default();
+
+        for value in 0..3 {
+            values.insert(allocator.create(), value * 2);
+        }
+
+        let pairs = values
+            .iter()
+            .zip(allocator.ids())
+            .into_iter()
+            .map(|(value, id)| (id.index_usize(), *value))
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec![(0, 0), (1, 2), (2, 4)], pairs);
+    }
+
+    #[test]
+    fn living_zips_with_component() {
+        let mut allocator = Allocator::<FixedArena>::default();
+        let mut values = Component::<FixedArena, u32>::default();
+
+        for value in 0..4 {
+            values.insert(allocator.create(), value);
+        }
+        allocator.retire(Id::first(1));
+
+        let sum: u32 = values
+            .iter()
+            .zip(allocator.living())
+            .into_iter()
+            .filter_map(|(value, alive)| if alive { Some(value) } else { None })
+            .sum();
+
+        assert_eq!(5, sum);
+    }
 }
@@ -0,0 +1,221 @@
+use crate::{MemoryUsage, StateHash, StateHasher};
+use bit_vec::BitVec;
+use std::collections::VecDeque;
+
+/// The order in which a `DynamicAllocator` reuses the indices of killed entities, chosen per arena
+/// with `Arena::REUSE`.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum Reuse {
+    /// The most recently killed index is reused first.
+    #[default]
+    Lifo,
+    /// The least recently killed index is reused first, so that killed ids take longer to be
+    /// reused. Useful when debugging stale ids.
+    Fifo,
+    /// The lowest free index is reused first, which keeps living entities packed together. Free
+    /// indices are kept in a bitset that is scanned from the lowest free index.
+    Lowest,
+}
+
+#[derive(Debug)]
+pub(crate) enum FreeList {
+    Lifo(Vec<usize>),
+    Fifo(VecDeque<usize>),
+    Lowest(FreeBits),
+}
+
+/// A bitset of free indices, with a lower bound on the lowest one so that popping does not scan
+/// the indices that have already been reused.
+#[derive(Debug, Default)]
+pub(crate) struct FreeBits {
+    free: BitVec,
+    len: usize,
+    lowest: usize,
+}
+
+impl FreeBits {
+    fn push(&mut self, index: usize) {
+        if self.free.len() <= index {
+            self.free.grow(index + 1 - self.free.len(), false);
+        }
+
+        self.free.set(index, true);
+        self.len += 1;
+        self.lowest = self.lowest.min(index);
+    }
+
+    fn pop(&mut self) -> Option<usize> {
+        let index = self.nth(0)?;
+
+        self.free.set(index, false);
+        self.len -= 1;
+        self.lowest = index + 1;
+
+        Some(index)
+    }
+
+    /// The `n`th lowest free index, counting from zero.
+    fn nth(&self, mut n: usize) -> Option<usize> {
+        if n >= self.len {
+            return None;
+        }
+
+        let bits = 32;
+        let start = self.lowest / bits;
+
+        for (i, block) in self.free.blocks().enumerate().skip(start) {
+            let mut block = if i == start {
+                block & (u32::MAX << (self.lowest % bits))
+            } else {
+                block
+            };
+
+            let ones = block.count_ones() as usize;
+            if n >= ones {
+                n -= ones;
+                continue;
+            }
+
+            for _ in 0..n {
+                block &= block - 1;
+            }
+
+            return Some(i * bits + block.trailing_zeros() as usize);
+        }
+
+        None
+    }
+
+    fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.free
+            .iter()
+            .enumerate()
+            .filter_map(|(index, free)| if free { Some(index) } else { None })
+    }
+}
+
+impl FreeList {
+    pub fn new(reuse: Reuse) -> Self {
+        match reuse {
+            Reuse::Lifo => FreeList::Lifo(Vec::new()),
+            Reuse::Fifo => FreeList::Fifo(VecDeque::new()),
+            Reuse::Lowest => FreeList::Lowest(FreeBits::default()),
+        }
+    }
+
+    pub fn push(&mut self, index: usize) {
+        match self {
+            FreeList::Lifo(list) => list.push(index),
+            FreeList::Fifo(list) => list.push_back(index),
+            FreeList::Lowest(bits) => bits.push(index),
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<usize> {
+        match self {
+            FreeList::Lifo(list) => list.pop(),
+            FreeList::Fifo(list) => list.pop_front(),
+            FreeList::Lowest(bits) => bits.pop(),
+        }
+    }
+
+    /// The index that the `n`th call to `pop` would return, counting from zero.
+    pub fn nth(&self, n: usize) -> Option<usize> {
+        match self {
+            FreeList::Lifo(list) => list.len().checked_sub(n + 1).map(|i| list[i]),
+            FreeList::Fifo(list) => list.get(n).copied(),
+            FreeList::Lowest(bits) => bits.nth(n),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            FreeList::Lifo(list) => list.len(),
+            FreeList::Fifo(list) => list.len(),
+            FreeList::Lowest(bits) => bits.len,
+        }
+    }
+}
+
+impl MemoryUsage for FreeList {
+    fn memory_usage(&self) -> usize {
+        match self {
+            FreeList::Lifo(list) => list.capacity() * std::mem::size_of::<usize>(),
+            FreeList::Fifo(list) => list.capacity() * std::mem::size_of::<usize>(),
+            FreeList::Lowest(bits) => bits.free.capacity() / 8,
+        }
+    }
+}
+
+/// Hashes the free indices in the order that they are stored, which determines the ids of the next
+/// entities to be created.
+impl StateHash for FreeList {
+    fn state_hash(&self, hasher: &mut StateHasher) {
+        match self {
+            FreeList::Lifo(list) => list.state_hash(hasher),
+            FreeList::Fifo(list) => {
+                hasher.write_usize(list.len());
+                list.iter().for_each(|index| index.state_hash(hasher));
+            }
+            FreeList::Lowest(bits) => {
+                hasher.write_usize(bits.len);
+                bits.iter().for_each(|index| index.state_hash(hasher));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn free_list(reuse: Reuse) -> FreeList {
+        let mut list = FreeList::new(reuse);
+        for index in [3, 1, 4, 2].iter() {
+            list.push(*index);
+        }
+        list
+    }
+
+    fn pop_all(reuse: Reuse) -> Vec<usize> {
+        let mut list = free_list(reuse);
+
+        let len = list.len();
+
+        let nth = (0..=len).map(|n| list.nth(n)).collect::<Vec<_>>();
+        let popped = (0..=len).map(|_| list.pop()).collect::<Vec<_>>();
+        assert_eq!(nth, popped);
+
+        popped.into_iter().flatten().collect()
+    }
+
+    #[test]
+    fn lifo() {
+        assert_eq!(vec![2, 4, 1, 3], pop_all(Reuse::Lifo));
+    }
+
+    #[test]
+    fn fifo() {
+        assert_eq!(vec![3, 1, 4, 2], pop_all(Reuse::Fifo));
+    }
+
+    #[test]
+    fn lowest() {
+        assert_eq!(vec![1, 2, 3, 4], pop_all(Reuse::Lowest));
+    }
+
+    #[test]
+    fn lowest_across_blocks() {
+        let mut list = FreeList::new(Reuse::Lowest);
+        for index in [70, 3, 40, 35].iter() {
+            list.push(*index);
+        }
+
+        assert_eq!(Some(3), list.pop());
+        assert_eq!(Some(40), list.nth(1));
+        list.push(1);
+
+        let popped = (0..5).map(|_| list.pop()).collect::<Vec<_>>();
+        assert_eq!(vec![Some(1), Some(35), Some(40), Some(70), None], popped);
+    }
+}
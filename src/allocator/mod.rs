@@ -1,12 +1,18 @@
 use crate::*;
 pub use alloc_gen::{AllocGen, GenerationCmp};
-pub use dynamic::DynamicAllocator;
+pub use dynamic::{AllocatorStats, DynamicAllocator, GenOverflow};
 pub use fixed::FixedAllocator;
+pub use free_list::Reuse;
 use std::ops::{Deref, DerefMut};
+pub use subset::Subset;
+pub use token::ValidToken;
 
 mod alloc_gen;
 mod dynamic;
 mod fixed;
+mod free_list;
+mod subset;
+mod token;
 
 // #[cfg_attr(feature="serde", derive(Serialize, Deserialize))]
 #[derive(Debug)]
@@ -36,6 +42,24 @@ impl<A: Arena> DerefMut for Allocator<A> {
     }
 }
 
+impl<A: Arena> MemoryUsage for Allocator<A>
+where
+    A::Allocator: MemoryUsage,
+{
+    fn memory_usage(&self) -> usize {
+        self.allocator.memory_usage()
+    }
+}
+
+impl<A: Arena> StateHash for Allocator<A>
+where
+    A::Allocator: StateHash,
+{
+    fn state_hash(&self, hasher: &mut StateHasher) {
+        self.allocator.state_hash(hasher);
+    }
+}
+
 impl<A: Arena<Allocator = DynamicAllocator<A>>> Allocator<A> {
     pub fn with_capacity(capacity: usize) -> Self {
         Self {
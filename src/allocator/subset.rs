@@ -0,0 +1,244 @@
+use crate::*;
+
+/// The allocator of a [`SubArena`]: the set of entities of the parent arena that are members.
+///
+/// Members share their index and generation with the parent arena, so components of the parent
+/// can be indexed by member ids. Remove members when they are killed in the parent arena.
+///
+/// If the parent arena is dynamic, members are validated against the parent allocator with
+/// `validate_in` and `ids_in`, so that a member killed in the parent is never valid.
+#[derive(Debug)]
+pub struct Subset<A: Arena> {
+    members: Vec<Option<Id<A>>>,
+    len: usize,
+}
+
+impl<A: Arena> Default for Subset<A> {
+    fn default() -> Self {
+        Self {
+            members: vec![],
+            len: 0,
+        }
+    }
+}
+
+impl<A: SubArena> Subset<A> {
+    /// Adds an entity of the parent arena to the subset.
+    pub fn insert<I: ValidId<A::Super>>(&mut self, id: I) -> Id<A> {
+        let id = id.id();
+        let index = id.index_usize();
+
+        if self.members.len() <= index {
+            self.members.resize(index + 1, None);
+        }
+
+        let member = &mut self.members[index];
+        if member.is_none() {
+            self.len += 1;
+        }
+
+        let id = Id::from_bits(id.bits());
+        *member = Some(id);
+        id
+    }
+
+    /// Removes an entity from the subset. Returns false if it was not a member.
+    pub fn remove(&mut self, id: Id<A::Super>) -> bool {
+        if self.downcast(id).is_some() {
+            self.members[id.index_usize()] = None;
+            self.len -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn contains(&self, id: Id<A::Super>) -> bool {
+        self.downcast(id).is_some()
+    }
+
+    /// Converts an id of the parent arena if it is a member of the subset.
+    pub fn downcast(&self, id: Id<A::Super>) -> Option<Id<A>> {
+        self.member(Id::from_bits(id.bits()))
+    }
+
+    pub fn validate(&self, id: Id<A>) -> Option<Valid<'_, Id<A>>>
+    where
+        A::Super: Arena<Allocator = FixedAllocator<A::Super>>,
+    {
+        self.member(id).map(Valid::new)
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = Valid<'_, Id<A>>> + '_
+    where
+        A::Super: Arena<Allocator = FixedAllocator<A::Super>>,
+    {
+        self.members.iter().flatten().copied().map(Valid::new)
+    }
+
+    /// Validates a member against the dynamic parent arena. Members that have been killed in the
+    /// parent arena are not valid, even if they have not been removed from the subset.
+    pub fn validate_in<'a>(
+        &'a self,
+        id: Id<A>,
+        parent: &'a DynamicAllocator<A::Super>,
+    ) -> Option<Valid<'a, Id<A>>> {
+        self.member(id)
+            .filter(|id| parent.is_alive(id.upcast()))
+            .map(Valid::new)
+    }
+
+    /// The members that are alive in the dynamic parent arena.
+    pub fn ids_in<'a>(
+        &'a self,
+        parent: &'a DynamicAllocator<A::Super>,
+    ) -> impl Iterator<Item = Valid<'a, Id<A>>> + 'a {
+        self.members
+            .iter()
+            .flatten()
+            .copied()
+            .filter(move |id| parent.is_alive(id.upcast()))
+            .map(Valid::new)
+    }
+
+    fn member(&self, id: Id<A>) -> Option<Id<A>> {
+        match self.members.get(id.index_usize()) {
+            Some(Some(member)) if *member == id => Some(id),
+            _ => None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<A: SubArena> Validate<A> for Subset<A>
+where
+    A::Super: Arena<Allocator = FixedAllocator<A::Super>>,
+{
+    fn validate(&self, id: Id<A>) -> Option<Valid<'_, Id<A>>> {
+        Subset::validate(self, id)
+    }
+}
+
+impl<A: Arena> MemoryUsage for Subset<A> {
+    fn memory_usage(&self) -> usize {
+        self.members.capacity() * std::mem::size_of::<Option<Id<A>>>()
+    }
+}
+
+impl<A: Arena> StateHash for Subset<A> {
+    fn state_hash(&self, hasher: &mut StateHasher) {
+        self.members.state_hash(hasher);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct Body;
+
+    fixed_arena!(Body);
+
+    #[derive(Debug)]
+    struct Planet;
+
+    sub_arena!(Planet, Body);
+
+    #[derive(Debug)]
+    struct Ship;
+
+    dynamic_arena!(Ship);
+
+    #[derive(Debug)]
+    struct Cargo;
+
+    sub_arena!(Cargo, Ship);
+
+    #[test]
+    fn upcast_and_downcast() {
+        let mut bodies = Allocator::<Body>::default();
+        let mut planets = Allocator::<Planet>::default();
+
+        let star = bodies.create();
+        let earth = bodies.create();
+        let planet = planets.insert(earth);
+
+        assert_eq!(earth, planet.upcast());
+        assert_eq!(Some(planet), planets.downcast(earth));
+        assert_eq!(None, planets.downcast(star));
+        assert_eq!(1, planets.len());
+    }
+
+    #[test]
+    fn index_super_component_with_sub_id() {
+        let mut bodies = Allocator::<Body>::default();
+        let mut planets = Allocator::<Planet>::default();
+        let mut mass = Component::<Body, f64>::default();
+
+        mass.insert(bodies.create(), 1.0);
+        let earth = bodies.create();
+        mass.insert(earth, 2.0);
+
+        let planet = planets.insert(earth);
+
+        assert_eq!(&2.0, mass.get(planet));
+        assert_eq!(&2.0, mass.get(planets.validate(planet).unwrap()));
+    }
+
+    #[test]
+    fn dynamic_super_requires_valid_sub_id() {
+        let mut ships = Allocator::<Ship>::default();
+        let mut cargo = Allocator::<Cargo>::default();
+        let mut mass = Component::<Ship, f64>::default();
+
+        let ship = ships.create();
+        mass.insert(ship, 3.0);
+        let ship = ship.value;
+        let hauler = cargo.insert(ships.validate(ship).unwrap());
+
+        let valid = cargo.validate_in(hauler, &ships).unwrap();
+        assert_eq!(&3.0, mass.get(valid));
+
+        ships.kill(ship);
+        assert!(cargo.remove(ship));
+        assert!(cargo.validate_in(hauler, &ships).is_none());
+        assert!(cargo.is_empty());
+    }
+
+    #[test]
+    fn member_killed_in_dynamic_super_is_not_valid() {
+        let mut ships = Allocator::<Ship>::default();
+        let mut cargo = Allocator::<Cargo>::default();
+
+        let ship = ships.create().value;
+        let hauler = cargo.insert(ships.validate(ship).unwrap());
+        ships.kill(ship);
+
+        assert!(cargo.contains(ship));
+        assert!(cargo.validate_in(hauler, &ships).is_none());
+        assert_eq!(0, cargo.ids_in(&ships).count());
+    }
+
+    #[test]
+    fn stale_super_id_is_not_a_member() {
+        let mut ships = Allocator::<Ship>::default();
+        let mut cargo = Allocator::<Cargo>::default();
+
+        let old = ships.create().value;
+        ships.kill(old);
+        let new = ships.create().value;
+        cargo.insert(ships.validate(new).unwrap());
+
+        assert!(cargo.contains(new));
+        assert!(!cargo.contains(old));
+        assert!(!cargo.remove(old));
+    }
+}
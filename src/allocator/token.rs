@@ -0,0 +1,83 @@
+use crate::*;
+use std::marker::PhantomData;
+
+/// Proof that a particular `DynamicAllocator` is borrowed, issued by [`DynamicAllocator::with_token`].
+///
+/// The lifetime `'id` is unique to each call of `with_token` and invariant, so it brands the token
+/// with its allocator: two tokens only have the same type if they came from the same call, and ids
+/// validated through the token cannot outlive the closure.
+pub struct ValidToken<'id, A: Arena> {
+    alloc: &'id DynamicAllocator<A>,
+    brand: PhantomData<fn(&'id ()) -> &'id ()>,
+}
+
+impl<A: Arena> Clone for ValidToken<'_, A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<A: Arena> Copy for ValidToken<'_, A> {}
+
+impl<A: Arena> std::fmt::Debug for ValidToken<'_, A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ValidToken").finish()
+    }
+}
+
+impl<'id, A: Arena> ValidToken<'id, A> {
+    pub(crate) fn new(alloc: &'id DynamicAllocator<A>) -> Self {
+        Self {
+            alloc,
+            brand: PhantomData,
+        }
+    }
+
+    pub fn validate(self, id: Id<A>) -> Option<Valid<'id, Id<A>>> {
+        self.alloc.validate(id)
+    }
+
+    pub fn is_alive(self, id: Id<A>) -> bool {
+        self.alloc.is_alive(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::allocator::test::GenerationalArena;
+
+    fn count_valid(
+        token: ValidToken<'_, GenerationalArena>,
+        ids: &[Id<GenerationalArena>],
+    ) -> usize {
+        ids.iter().filter_map(|id| token.validate(*id)).count()
+    }
+
+    #[test]
+    fn token_validates_living_ids() {
+        let mut alloc = Allocator::<GenerationalArena>::default();
+        let a = alloc.create().value;
+        let b = alloc.create().value;
+        alloc.kill(a);
+
+        assert_eq!(1, alloc.with_token(|token| count_valid(token, &[a, b])));
+        assert_eq!(
+            Some(b),
+            alloc.with_token(|token| token.validate(b).map(|b| b.id()))
+        );
+    }
+
+    #[test]
+    fn map_keeps_lifetime() {
+        let mut alloc = Allocator::<GenerationalArena>::default();
+        let id = alloc.create().value;
+
+        // SAFETY: the id is kept unchanged
+        let pair = unsafe { alloc.validate(id).unwrap().map(|id| (id, 1)) };
+
+        assert_eq!((id, 1), pair.value);
+        // SAFETY: the id is taken from the pair
+        assert_eq!(id, unsafe { pair.map_ref(|(id, _)| *id) }.value);
+    }
+}
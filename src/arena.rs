@@ -1,39 +1,170 @@
 use crate::*;
-use std::fmt::{Display, Formatter, Result};
+use std::fmt::{Formatter, Result};
 
 pub trait Arena {
     type Allocator;
+
+    /// How the arena's ids are split between index and generation, see [`Layout64`].
+    type Layout: IdLayout;
+
+    /// The name of the arena in the `Display` form of its ids and in state digests. The arena
+    /// macros use the type as written, e.g., `Colony`.
+    const NAME: &'static str;
+
+    /// The order in which a `DynamicAllocator` reuses the indices of killed entities.
+    const REUSE: Reuse = Reuse::Lifo;
+
+    /// What a `DynamicAllocator` does when a slot's generation overflows.
+    const OVERFLOW: GenOverflow = GenOverflow::Retire;
 }
 
 #[macro_export]
 macro_rules! fixed_arena {
     ($arena:ty) => {
+        $crate::fixed_arena!($arena, $crate::Layout64);
+    };
+    ($arena:ty, $layout:ty) => {
         impl $crate::Arena for $arena {
             type Allocator = $crate::FixedAllocator<Self>;
+            type Layout = $layout;
+            const NAME: &'static str = stringify!($arena);
         }
+
+        const _: () = <$layout as $crate::IdLayout>::CHECK;
     };
 }
 
+/// Implements [`Arena`] with a `DynamicAllocator`. The layout defaults to [`Layout64`], an
+/// optional `reuse = Reuse::Fifo` sets [`Arena::REUSE`], and an optional
+/// `overflow = GenOverflow::Wrap` after it sets [`Arena::OVERFLOW`].
 #[macro_export]
 macro_rules! dynamic_arena {
     ($arena:ty) => {
+        $crate::dynamic_arena!($arena, $crate::Layout64);
+    };
+    ($arena:ty, reuse = $reuse:expr) => {
+        $crate::dynamic_arena!($arena, $crate::Layout64, reuse = $reuse);
+    };
+    ($arena:ty, overflow = $overflow:expr) => {
+        $crate::dynamic_arena!($arena, $crate::Layout64, overflow = $overflow);
+    };
+    ($arena:ty, reuse = $reuse:expr, overflow = $overflow:expr) => {
+        $crate::dynamic_arena!(
+            $arena,
+            $crate::Layout64,
+            reuse = $reuse,
+            overflow = $overflow
+        );
+    };
+    ($arena:ty, $layout:ty) => {
+        $crate::dynamic_arena!($arena, $layout, reuse = $crate::Reuse::Lifo);
+    };
+    ($arena:ty, $layout:ty, reuse = $reuse:expr) => {
+        $crate::dynamic_arena!(
+            $arena,
+            $layout,
+            reuse = $reuse,
+            overflow = $crate::GenOverflow::Retire
+        );
+    };
+    ($arena:ty, $layout:ty, overflow = $overflow:expr) => {
+        $crate::dynamic_arena!(
+            $arena,
+            $layout,
+            reuse = $crate::Reuse::Lifo,
+            overflow = $overflow
+        );
+    };
+    ($arena:ty, $layout:ty, reuse = $reuse:expr, overflow = $overflow:expr) => {
         impl $crate::Arena for $arena {
             type Allocator = $crate::DynamicAllocator<Self>;
+            type Layout = $layout;
+            const NAME: &'static str = stringify!($arena);
+            const REUSE: $crate::Reuse = $reuse;
+            const OVERFLOW: $crate::GenOverflow = $overflow;
         }
+
+        const _: () = <$layout as $crate::IdLayout>::CHECK;
     };
 }
 
-pub trait DisplayEntity: Sized {
-    fn fmt_entity<I: ValidId<Self>>(&self, id: I, f: &mut Formatter) -> Result;
+/// An arena whose entities are a subset of the entities of another arena, such as planets within
+/// bodies. Declare with `sub_arena!`.
+///
+/// Ids of the subset convert to ids of the parent with `Id::upcast`, and back with
+/// `Subset::downcast`.
+pub trait SubArena: Arena<Allocator = Subset<Self>> + Sized {
+    type Super: Arena<Layout = Self::Layout>;
 }
 
-pub struct Entity<'a, A, I> {
-    pub arena: &'a A,
-    pub id: I,
+/// Declares a [`SubArena`] of `$super`.
+///
+/// Ids of the subset can index components of the parent arena: `Valid` ids always, and plain ids
+/// if the parent arena is fixed.
+#[macro_export]
+macro_rules! sub_arena {
+    ($arena:ty, $super:ty) => {
+        impl $crate::Arena for $arena {
+            type Allocator = $crate::Subset<Self>;
+            type Layout = <$super as $crate::Arena>::Layout;
+            const NAME: &'static str = stringify!($arena);
+        }
+
+        impl $crate::SubArena for $arena {
+            type Super = $super;
+        }
+
+        // The impls name `$super` rather than a type parameter so that they satisfy the orphan
+        // rules when the macro is used in another crate. `for<'x>` keeps the fixed-parent bound
+        // from being rejected as trivially false when the parent is dynamic.
+
+        // SAFETY: a sub-arena id is the id of an entity of the fixed parent arena
+        unsafe impl $crate::__private::Sealed<$super> for $crate::Id<$arena> where
+            for<'x> $super: $crate::Arena<Allocator = $crate::FixedAllocator<$super>>
+        {
+        }
+
+        impl $crate::ValidId<$super> for $crate::Id<$arena>
+        where
+            for<'x> $super: $crate::Arena<Allocator = $crate::FixedAllocator<$super>>,
+        {
+            fn index(self) -> usize {
+                $crate::ValidId::index(self.upcast())
+            }
+
+            fn id(self) -> $crate::Id<$super> {
+                self.upcast()
+            }
+        }
+
+        // SAFETY: a valid member of the subset is a living entity of the parent arena
+        unsafe impl $crate::__private::Sealed<$super> for $crate::Valid<'_, $crate::Id<$arena>> {}
+
+        impl $crate::ValidId<$super> for $crate::Valid<'_, $crate::Id<$arena>> {
+            fn index(self) -> usize {
+                $crate::ValidId::index(self.upcast())
+            }
+
+            fn id(self) -> $crate::Id<$super> {
+                self.value().upcast()
+            }
+        }
+
+        // SAFETY: as above
+        unsafe impl $crate::__private::Sealed<$super> for $crate::Valid<'_, &$crate::Id<$arena>> {}
+
+        impl $crate::ValidId<$super> for $crate::Valid<'_, &$crate::Id<$arena>> {
+            fn index(self) -> usize {
+                $crate::ValidId::index(self.copied().upcast())
+            }
+
+            fn id(self) -> $crate::Id<$super> {
+                self.value().upcast()
+            }
+        }
+    };
 }
 
-impl<A: Arena + DisplayEntity, I: ValidId<A> + Copy> Display for Entity<'_, A, I> {
-    fn fmt(&self, f: &mut Formatter) -> Result {
-        self.arena.fmt_entity(self.id, f)
-    }
+pub trait DisplayEntity: Arena + Sized {
+    fn fmt_entity<I: ValidId<Self>>(&self, id: I, f: &mut Formatter) -> Result;
 }
@@ -0,0 +1,287 @@
+use crate::*;
+
+impl World {
+    /// Kills the owners of links to a killed entity, which may cascade further.
+    ///
+    /// Cycles of rules end when they reach an entity that is already dead.
+    pub fn kill_linked<A, B, S, D, F>(&mut self, storage: F)
+    where
+        A: HasAllocator + Arena<Allocator = DynamicAllocator<A>> + 'static,
+        B: Arena + 'static,
+        S: Send + Sync + 'static,
+        D: Relation<B, Owner = A>,
+        F: Fn(&S) -> &D + Send + Sync + 'static,
+    {
+        self.on_kill::<B, _>(move |world, id| {
+            let linked = storage(&*world.try_get::<S>()?).linked(id);
+
+            let owners = {
+                let arena = world.try_get::<A>()?;
+                let ids = Iterator::map(arena.allocator().ids(), |id| id.map(|id| *id.value))
+                    .collect::<Vec<_>>();
+
+                linked
+                    .into_iter()
+                    .filter_map(|i| ids.get(i).copied().flatten())
+                    .collect::<Vec<_>>()
+            };
+
+            for owner in owners {
+                world.kill(owner)?;
+            }
+            Ok(())
+        });
+    }
+
+    /// Kills the entities that own the rows of a table that link to a killed entity. `storage`
+    /// returns the table's id column along with its link column.
+    ///
+    /// This is [`World::kill_linked`] for links stored in an `IdColumn`, whose rows are not
+    /// indexed by the owning entities.
+    pub fn kill_linked_rows<A, B, S, C, F>(&mut self, storage: F)
+    where
+        A: HasAllocator + Arena<Allocator = DynamicAllocator<A>> + 'static,
+        B: Arena + 'static,
+        S: Send + Sync + 'static,
+        F: Fn(&S) -> (&Column<C, Id<A>>, &IdColumn<C, B>) + Send + Sync + 'static,
+    {
+        self.on_kill::<B, _>(move |world, id| {
+            let owners = {
+                let resource = world.try_get::<S>()?;
+                let (ids, links) = storage(&resource);
+
+                Iterator::map(links.linked(id).into_iter(), Index::new)
+                    .filter_map(|index| ids.get(&index).copied())
+                    .collect::<Vec<_>>()
+            };
+
+            for owner in owners {
+                world.kill(owner)?;
+            }
+            Ok(())
+        });
+    }
+
+    /// Removes links to a killed entity.
+    pub fn nullify_links<B, S, D, F>(&mut self, storage: F)
+    where
+        B: Arena + 'static,
+        S: Send + Sync + 'static,
+        D: NullableRelation<B>,
+        F: Fn(&mut S) -> &mut D + Send + Sync + 'static,
+    {
+        self.on_kill::<B, _>(move |world, id| {
+            storage(&mut *world.try_get_mut::<S>()?).unlink(id);
+            Ok(())
+        });
+    }
+
+    /// Replaces links to a killed entity with links to `to`.
+    ///
+    /// When `to` itself is killed its links are left to the rules registered after this one, so
+    /// pair it with [`World::kill_linked`] or [`World::nullify_links`] unless `to` outlives the
+    /// links.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `to` is dead, or if arena `B` is not in the world.
+    pub fn reassign_links<B, S, D, F>(&mut self, to: Id<B>, storage: F)
+    where
+        B: HasAllocator + Send + Sync + 'static,
+        B::Allocator: Validate<B>,
+        S: Send + Sync + 'static,
+        D: Relation<B>,
+        F: Fn(&mut S) -> &mut D + Send + Sync + 'static,
+    {
+        assert!(
+            self.get::<B>().allocator().validate(to).is_some(),
+            "cannot reassign links to a dead entity: {:?}",
+            to
+        );
+
+        self.on_kill::<B, _>(move |world, id| {
+            if id != to {
+                storage(&mut *world.try_get_mut::<S>()?).relink(id, to);
+            }
+            Ok(())
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct Colony {
+        alloc: Allocator<Self>,
+        government: Component<Self, Id<Government>>,
+        sister: IdLink<Self, Colony>,
+    }
+
+    dynamic_arena!(Colony);
+
+    impl HasAllocator for Colony {
+        fn allocator(&self) -> &Allocator<Self> {
+            &self.alloc
+        }
+
+        fn allocator_mut(&mut self) -> &mut Allocator<Self> {
+            &mut self.alloc
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct Government {
+        alloc: Allocator<Self>,
+        capital: IdLink<Self, Colony>,
+    }
+
+    dynamic_arena!(Government);
+
+    impl HasAllocator for Government {
+        fn allocator(&self) -> &Allocator<Self> {
+            &self.alloc
+        }
+
+        fn allocator_mut(&mut self) -> &mut Allocator<Self> {
+            &mut self.alloc
+        }
+    }
+
+    struct Ids {
+        govts: [Id<Government>; 2],
+        colonies: [Id<Colony>; 3],
+    }
+
+    /// Two governments, the first with two colonies and the second with one. The first two
+    /// colonies are each other's sisters.
+    fn world() -> (World, Ids) {
+        let mut govt = Government::default();
+        let mut colony = Colony::default();
+
+        let govts = [govt.alloc.create().value, govt.alloc.create().value];
+
+        let mut colonies = vec![];
+        for g in [govts[0], govts[0], govts[1]].iter() {
+            let id = colony.alloc.create();
+            colony.government.insert(id, *g);
+            colony.sister.insert(id, None::<Valid<Id<Colony>>>);
+            colonies.push(id.value);
+        }
+
+        let a = colony.alloc.validate(colonies[0]).unwrap();
+        let b = colony.alloc.validate(colonies[1]).unwrap();
+        colony.sister.insert(a, Some(b));
+        colony.sister.insert(b, Some(a));
+
+        for (g, c) in govts.iter().zip(&[colonies[0], colonies[2]]) {
+            let g = govt.alloc.validate(*g).unwrap();
+            govt.capital.insert(g, colony.alloc.validate(*c));
+        }
+
+        let mut world = World::default();
+        world.insert(govt);
+        world.insert(colony);
+
+        let colonies = [colonies[0], colonies[1], colonies[2]];
+        (world, Ids { govts, colonies })
+    }
+
+    fn living_colonies(world: &World, ids: &Ids) -> Vec<bool> {
+        let colony = world.get::<Colony>();
+        let alive = |id: &Id<Colony>| colony.alloc.is_alive(*id);
+        ids.colonies.iter().map(alive).collect()
+    }
+
+    #[test]
+    fn kill_linked_owners() {
+        let (mut world, ids) = world();
+        world.kill_linked::<Colony, Government, _, _, _>(|c: &Colony| &c.government);
+
+        world.kill(ids.govts[0]).unwrap();
+
+        assert_eq!(vec![false, false, true], living_colonies(&world, &ids));
+    }
+
+    #[derive(Default)]
+    struct Missions {
+        colony: Column<Missions, Id<Colony>>,
+        government: IdColumn<Missions, Government>,
+    }
+
+    #[test]
+    fn kill_linked_rows_of_table() {
+        let (mut world, ids) = world();
+        let mut missions = Missions::default();
+        {
+            let govt = world.get::<Government>();
+            for (c, g) in [(2, 1), (0, 0)].iter() {
+                missions.colony.push(ids.colonies[*c]);
+                missions.government.push(govt.alloc.validate(ids.govts[*g]));
+            }
+        }
+        world.insert(missions);
+        world.kill_linked_rows::<Colony, Government, _, _, _>(|m: &Missions| {
+            (&m.colony, &m.government)
+        });
+
+        world.kill(ids.govts[1]).unwrap();
+
+        assert_eq!(vec![true, true, false], living_colonies(&world, &ids));
+    }
+
+    #[test]
+    fn transitive_cascade_nullifies_links() {
+        let (mut world, ids) = world();
+        world.kill_linked::<Colony, Government, _, _, _>(|c: &Colony| &c.government);
+        world.nullify_links::<Colony, _, _, _>(|g: &mut Government| &mut g.capital);
+
+        world.kill(ids.govts[0]).unwrap();
+
+        let govt = world.get::<Government>();
+        let g = govt.alloc.validate(ids.govts[1]).unwrap();
+        assert_eq!(&Some(ids.colonies[2]), govt.capital.get(g));
+        assert!(govt.capital.linked(ids.colonies[0]).is_empty());
+    }
+
+    #[test]
+    fn cycles_end_at_dead_entities() {
+        let (mut world, ids) = world();
+        world.kill_linked::<Colony, Colony, _, _, _>(|c: &Colony| &c.sister);
+
+        assert_eq!(Ok(true), world.kill(ids.colonies[1]));
+
+        assert_eq!(vec![false, false, true], living_colonies(&world, &ids));
+    }
+
+    #[test]
+    fn reassign_links_to_default() {
+        let (mut world, ids) = world();
+        let independent = ids.govts[1];
+        world
+            .reassign_links::<Government, _, _, _>(independent, |c: &mut Colony| &mut c.government);
+        world.kill_linked::<Colony, Government, _, _, _>(|c: &Colony| &c.government);
+
+        world.kill(ids.govts[0]).unwrap();
+
+        let linked = world.get::<Colony>().government.linked(independent);
+        assert_eq!(vec![0, 1, 2], linked);
+        assert_eq!(vec![true, true, true], living_colonies(&world, &ids));
+
+        world.kill(independent).unwrap();
+
+        assert_eq!(vec![false, false, false], living_colonies(&world, &ids));
+    }
+
+    #[test]
+    #[should_panic(expected = "dead entity")]
+    fn reassign_links_to_dead_default() {
+        let (mut world, ids) = world();
+        world.kill(ids.govts[1]).unwrap();
+
+        world.reassign_links::<Government, _, _, _>(ids.govts[1], |c: &mut Colony| {
+            &mut c.government
+        });
+    }
+}
@@ -0,0 +1,530 @@
+use crate::*;
+
+/// The clones made so far, and options for how to make them, see [`CloneEntity`].
+///
+/// Reuse the same context when cloning related entities of several arenas, so that links
+/// between them can be pointed at the clones.
+///
+/// Links are remapped when the entities that hold them are cloned, to the clones made so far with
+/// the context. Clone the entities that are linked to first, such as bodies before the colonies on
+/// them, or both ends of a link in the same call.
+#[derive(Debug)]
+pub struct CloneContext {
+    clones: HashMap<AnyId, AnyId>,
+    remap_links: bool,
+    deep: bool,
+}
+
+impl Default for CloneContext {
+    fn default() -> Self {
+        Self {
+            clones: Default::default(),
+            remap_links: true,
+            deep: false,
+        }
+    }
+}
+
+impl CloneContext {
+    /// Whether links to cloned entities are pointed at their clones. Defaults to true.
+    pub fn remap_links(mut self, remap_links: bool) -> Self {
+        self.remap_links = remap_links;
+        self
+    }
+
+    /// Whether rows of `IdMap`s and edges of `Graph`s that mention a cloned entity are also
+    /// copied. Defaults to false.
+    pub fn deep(mut self, deep: bool) -> Self {
+        self.deep = deep;
+        self
+    }
+
+    pub fn is_deep(&self) -> bool {
+        self.deep
+    }
+
+    pub fn remaps_links(&self) -> bool {
+        self.remap_links
+    }
+
+    /// Records that `to` is a clone of `from`.
+    pub fn insert<A: Arena + 'static>(&mut self, from: Id<A>, to: Id<A>) {
+        self.clones.insert(AnyId::new(from), AnyId::new(to));
+    }
+
+    /// The clone of an entity, if it has been cloned with this context.
+    pub fn get<A: Arena + 'static>(&self, id: Id<A>) -> Option<Id<A>> {
+        self.clones.get(&AnyId::new(id))?.downcast()
+    }
+
+    /// The clone of an entity if there is one, otherwise the entity itself.
+    pub fn remap<A: Arena + 'static>(&self, id: Id<A>) -> Id<A> {
+        self.get(id).unwrap_or(id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.clones.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clones.is_empty()
+    }
+}
+
+/// A link that can be pointed at the clone of the entity it links to.
+pub trait Remap {
+    fn remap(&mut self, cx: &CloneContext);
+}
+
+impl<A: Arena + 'static> Remap for Id<A> {
+    fn remap(&mut self, cx: &CloneContext) {
+        *self = cx.remap(*self);
+    }
+}
+
+impl<A: Arena + 'static> Remap for Option<Id<A>> {
+    fn remap(&mut self, cx: &CloneContext) {
+        if let Some(id) = self {
+            id.remap(cx);
+        }
+    }
+}
+
+/// Storage that links each entity of arena `A` to another entity, such as a `Component` of ids or
+/// an `IdLink`. The link is copied to each clone and then remapped, see [`CloneContext`].
+pub trait CloneLinks<A: Arena> {
+    type Link;
+
+    fn link<I: ValidId<A>>(&self, id: I) -> Self::Link;
+
+    fn set_link<I: ValidId<A>>(&mut self, id: I, link: Self::Link);
+
+    /// Points the link of `id` at the clone of the entity that it links to, if there is one.
+    fn remap_link<I: ValidId<A>>(&mut self, id: I, cx: &CloneContext);
+}
+
+/// Storage whose rows mention entities of arena `A`, and are copied when the entities are cloned.
+///
+/// `clones` holds the `(source, clone)` pairs of a single call, and only rows that mention one of
+/// the sources are copied. The context is used to point the rest of the row at earlier clones.
+pub trait CloneRows<A: Arena> {
+    /// Copies every row that mentions a source, replacing cloned entities with their clones.
+    fn clone_rows(&mut self, clones: &[(Id<A>, Id<A>)], cx: &CloneContext);
+
+    /// Copies rows that mention a source into storage of another arena instance, skipping rows
+    /// that mention entities that have not been cloned.
+    fn clone_rows_into(&self, dest: &mut Self, clones: &[(Id<A>, Id<A>)], cx: &CloneContext);
+}
+
+/// An arena struct whose entities can be cloned, usually implemented with `clone_entity!`.
+pub trait CloneEntity: Arena + Sized {
+    /// Clones living entities within the arena and returns the ids of their clones. An id that
+    /// appears more than once is cloned once.
+    fn clone_entities(&mut self, ids: &[Id<Self>], cx: &mut CloneContext) -> Vec<Id<Self>>;
+
+    /// Clones living entities into another instance of the arena, such as one in another world.
+    /// An id that appears more than once is cloned once.
+    ///
+    /// Links to entities that have not been cloned still refer to the source.
+    fn clone_entities_into(
+        &self,
+        ids: &[Id<Self>],
+        dest: &mut Self,
+        cx: &mut CloneContext,
+    ) -> Vec<Id<Self>>;
+
+    /// Clones a single entity, or returns `None` if it is dead.
+    fn clone_entity(&mut self, id: Id<Self>) -> Option<Id<Self>> {
+        self.clone_entities(&[id], &mut CloneContext::default())
+            .pop()
+    }
+}
+
+/// Implements [`CloneEntity`] for an arena struct.
+///
+/// `components` are copied with `Clone`. `links` are copied and then remapped, see [`CloneLinks`].
+/// `rows` are `IdMap`s and `Graph`s keyed by the arena, copied if the context is deep. The
+/// optional `tables` are `table_array!`s keyed by the arena, whose rows are always copied.
+///
+/// ```ignore
+/// clone_entity! {
+///     Colony {
+///         alloc: alloc,
+///         components: [name, population],
+///         links: [body, government],
+///         rows: [trade],
+///         tables: [state],
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! clone_entity {
+    (
+        $arena:ty {
+            alloc: $alloc:ident,
+            components: [ $( $component:ident ),* $(,)? ],
+            links: [ $( $link:ident ),* $(,)? ],
+            rows: [ $( $row:ident ),* $(,)? ] $(,)?
+            $( tables: [ $( $table:ident ),* $(,)? ] $(,)? )?
+        }
+    ) => {
+        impl $crate::CloneEntity for $arena {
+            fn clone_entities(
+                &mut self,
+                ids: &[$crate::Id<Self>],
+                cx: &mut $crate::CloneContext,
+            ) -> Vec<$crate::Id<Self>> {
+                let mut clones = vec![];
+                let mut pairs = vec![];
+
+                for id in ids {
+                    if $crate::Validate::validate(&*self.$alloc, *id).is_none()
+                        || pairs.iter().any(|(src, _)| src == id)
+                    {
+                        continue;
+                    }
+
+                    let clone = $crate::ValidId::id(self.$alloc.create());
+                    let _src = $crate::Validate::validate(&*self.$alloc, *id).unwrap();
+                    let _dest = $crate::Validate::validate(&*self.$alloc, clone).unwrap();
+
+                    $(
+                        let value = Clone::clone(self.$component.get(_src));
+                        self.$component.insert(_dest, value);
+                    )*
+                    $(
+                        let link = $crate::CloneLinks::<Self>::link(&self.$link, _src);
+                        $crate::CloneLinks::<Self>::set_link(&mut self.$link, _dest, link);
+                    )*
+
+                    cx.insert(*id, clone);
+                    clones.push(clone);
+                    pairs.push((*id, clone));
+                }
+
+                if cx.remaps_links() {
+                    for clone in &clones {
+                        let _clone = $crate::Validate::validate(&*self.$alloc, *clone).unwrap();
+                        $(
+                            $crate::CloneLinks::<Self>::remap_link(&mut self.$link, _clone, cx);
+                        )*
+                    }
+                }
+
+                $( $(
+                    $crate::CloneRows::<Self>::clone_rows(&mut self.$table, &pairs, cx);
+                )* )?
+
+                if cx.is_deep() {
+                    $(
+                        $crate::CloneRows::<Self>::clone_rows(&mut self.$row, &pairs, cx);
+                    )*
+                }
+
+                clones
+            }
+
+            fn clone_entities_into(
+                &self,
+                ids: &[$crate::Id<Self>],
+                dest: &mut Self,
+                cx: &mut $crate::CloneContext,
+            ) -> Vec<$crate::Id<Self>> {
+                let mut clones = vec![];
+                let mut pairs = vec![];
+
+                for id in ids {
+                    let _src = match $crate::Validate::validate(&*self.$alloc, *id) {
+                        Some(src) => src,
+                        None => continue,
+                    };
+                    if pairs.iter().any(|(src, _)| src == id) {
+                        continue;
+                    }
+
+                    let clone = $crate::ValidId::id(dest.$alloc.create());
+                    let _clone = $crate::Validate::validate(&*dest.$alloc, clone).unwrap();
+
+                    $(
+                        dest.$component.insert(_clone, Clone::clone(self.$component.get(_src)));
+                    )*
+                    $(
+                        let link = $crate::CloneLinks::<Self>::link(&self.$link, _src);
+                        $crate::CloneLinks::<Self>::set_link(&mut dest.$link, _clone, link);
+                    )*
+
+                    cx.insert(*id, clone);
+                    clones.push(clone);
+                    pairs.push((*id, clone));
+                }
+
+                if cx.remaps_links() {
+                    for clone in &clones {
+                        let _clone = $crate::Validate::validate(&*dest.$alloc, *clone).unwrap();
+                        $(
+                            $crate::CloneLinks::<Self>::remap_link(&mut dest.$link, _clone, cx);
+                        )*
+                    }
+                }
+
+                $( $(
+                    $crate::CloneRows::<Self>::clone_rows_into(
+                        &self.$table,
+                        &mut dest.$table,
+                        &pairs,
+                        cx,
+                    );
+                )* )?
+
+                if cx.is_deep() {
+                    $(
+                        $crate::CloneRows::<Self>::clone_rows_into(
+                            &self.$row,
+                            &mut dest.$row,
+                            &pairs,
+                            cx,
+                        );
+                    )*
+                }
+
+                clones
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct Body {
+        alloc: Allocator<Self>,
+        name: Component<Self, &'static str>,
+        parent: Component<Self, Option<Id<Body>>>,
+    }
+
+    fixed_arena!(Body);
+
+    clone_entity! {
+        Body {
+            alloc: alloc,
+            components: [name],
+            links: [parent],
+            rows: [],
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct Colony {
+        alloc: Allocator<Self>,
+        population: Component<Self, f64>,
+        body: Component<Self, Id<Body>>,
+        tariff: IdMap<Self, f64>,
+        trade: Graph<Self, f64>,
+        sister: IdLink<Self, Colony>,
+    }
+
+    dynamic_arena!(Colony);
+
+    clone_entity! {
+        Colony {
+            alloc: alloc,
+            components: [population],
+            links: [body, sister],
+            rows: [tariff, trade],
+        }
+    }
+
+    impl Body {
+        fn create(&mut self, name: &'static str, parent: Option<Id<Body>>) -> Id<Self> {
+            let id = self.alloc.create();
+            self.name.insert(id, name);
+            self.parent.insert(id, parent);
+            id
+        }
+    }
+
+    impl Colony {
+        fn create(&mut self, population: f64, body: Id<Body>) -> Id<Self> {
+            let id = self.alloc.create();
+            self.population.insert(id, population);
+            self.body.insert(id, body);
+            self.sister.insert(id, None::<Valid<Id<Self>>>);
+            id.value
+        }
+
+        fn set_sister(&mut self, a: Id<Self>, b: Id<Self>) {
+            let b = self.alloc.validate(b);
+            self.sister.insert(self.alloc.validate(a).unwrap(), b);
+        }
+
+        fn sister(&self, id: Id<Self>) -> Option<Id<Self>> {
+            *self.sister.get(self.valid(id))
+        }
+
+        fn valid(&self, id: Id<Self>) -> Valid<'_, Id<Self>> {
+            self.alloc.validate(id).unwrap()
+        }
+
+        fn trade(&self, a: Id<Self>, b: Id<Self>) -> Option<f64> {
+            let edge = Edge::new_valid(self.valid(a), self.valid(b));
+            self.trade.get(edge).copied()
+        }
+    }
+
+    #[test]
+    fn clone_entity_copies_components() {
+        let mut body = Body::default();
+        let earth = body.create("Earth", None);
+        let mut colony = Colony::default();
+        let a = colony.create(10.0, earth);
+        let a_valid = colony.alloc.validate(a).unwrap();
+        colony.tariff.insert(a_valid, 0.1);
+
+        let clone = colony.clone_entity(a).unwrap();
+
+        assert_ne!(a, clone);
+        assert_eq!(&10.0, colony.population.get(colony.valid(clone)));
+        assert_eq!(&earth, colony.body.get(colony.valid(clone)));
+        assert_eq!(None, colony.tariff.get(colony.valid(clone)));
+
+        colony.alloc.kill(a);
+        assert_eq!(None, colony.clone_entity(a));
+    }
+
+    #[test]
+    fn links_within_clones_are_remapped() {
+        let mut body = Body::default();
+        let earth = body.create("Earth", None);
+        let luna = body.create("Luna", Some(earth));
+        let mut colony = Colony::default();
+        let a = colony.create(10.0, luna);
+
+        let mut cx = CloneContext::default();
+        let bodies = body.clone_entities(&[earth, luna], &mut cx);
+        let colonies = colony.clone_entities(&[a], &mut cx);
+
+        assert_eq!(&Some(bodies[0]), body.parent.get(bodies[1]));
+        assert_eq!(&None, body.parent.get(bodies[0]));
+        assert_eq!(&bodies[1], colony.body.get(colony.valid(colonies[0])));
+        assert_eq!(3, cx.len());
+    }
+
+    #[test]
+    fn id_links_are_remapped_across_calls() {
+        let mut body = Body::default();
+        let earth = body.create("Earth", None);
+        let mut colony = Colony::default();
+        let a = colony.create(10.0, earth);
+        let b = colony.create(20.0, earth);
+        let c = colony.create(30.0, earth);
+        colony.set_sister(a, b);
+        colony.set_sister(b, a);
+        colony.set_sister(c, a);
+
+        let mut cx = CloneContext::default();
+        let pair = colony.clone_entities(&[a, b], &mut cx);
+        let later = colony.clone_entities(&[c], &mut cx);
+
+        assert_eq!(Some(pair[1]), colony.sister(pair[0]));
+        assert_eq!(Some(pair[0]), colony.sister(pair[1]));
+        assert_eq!(Some(pair[0]), colony.sister(later[0]));
+        assert_eq!(Some(b), colony.sister(a));
+    }
+
+    #[test]
+    fn duplicate_ids_are_cloned_once() {
+        let mut body = Body::default();
+        let earth = body.create("Earth", None);
+        let mut colony = Colony::default();
+        let a = colony.create(10.0, earth);
+        let mut scenario = Colony::default();
+
+        let mut cx = CloneContext::default();
+        let clones = colony.clone_entities(&[a, a], &mut cx);
+        let into = colony.clone_entities_into(&[a, a], &mut scenario, &mut cx);
+
+        assert_eq!(1, clones.len());
+        assert_eq!(1, into.len());
+        assert_eq!(2, colony.alloc.len());
+        assert_eq!(1, scenario.alloc.len());
+    }
+
+    #[test]
+    fn links_are_kept_without_remapping() {
+        let mut body = Body::default();
+        let earth = body.create("Earth", None);
+        let luna = body.create("Luna", Some(earth));
+
+        let mut cx = CloneContext::default().remap_links(false);
+        let bodies = body.clone_entities(&[earth, luna], &mut cx);
+
+        assert_eq!(&Some(earth), body.parent.get(bodies[1]));
+    }
+
+    #[test]
+    fn deep_clone_copies_rows() {
+        let mut body = Body::default();
+        let earth = body.create("Earth", None);
+        let mut colony = Colony::default();
+        let a = colony.create(10.0, earth);
+        let b = colony.create(20.0, earth);
+        let a_valid = colony.alloc.validate(a).unwrap();
+        let b_valid = colony.alloc.validate(b).unwrap();
+        colony.trade.insert(Edge::new_valid(a_valid, b_valid), 1.5);
+        colony.tariff.insert(a_valid, 0.1);
+
+        let mut cx = CloneContext::default().deep(true);
+        let clone = colony.clone_entities(&[a], &mut cx)[0];
+
+        assert_eq!(Some(&0.1), colony.tariff.get(colony.valid(clone)));
+        assert_eq!(Some(1.5), colony.trade(clone, b));
+        assert_eq!(Some(1.5), colony.trade(a, b));
+    }
+
+    #[test]
+    fn deep_clone_only_copies_rows_of_this_call() {
+        let mut body = Body::default();
+        let earth = body.create("Earth", None);
+        let mut colony = Colony::default();
+        let a = colony.create(10.0, earth);
+        let b = colony.create(20.0, earth);
+        let a_valid = colony.alloc.validate(a).unwrap();
+        colony.tariff.insert(a_valid, 0.1);
+
+        let mut cx = CloneContext::default().deep(true);
+        let a_clone = colony.clone_entities(&[a], &mut cx)[0];
+        let valid = colony.alloc.validate(a_clone).unwrap();
+        colony.tariff.insert(valid, 0.5);
+        colony.clone_entities(&[b], &mut cx);
+
+        assert_eq!(Some(&0.5), colony.tariff.get(colony.valid(a_clone)));
+    }
+
+    #[test]
+    fn clone_into_other_arena() {
+        let mut body = Body::default();
+        let earth = body.create("Earth", None);
+        let mut colony = Colony::default();
+        let a = colony.create(10.0, earth);
+        let b = colony.create(20.0, earth);
+        let a_valid = colony.alloc.validate(a).unwrap();
+        let b_valid = colony.alloc.validate(b).unwrap();
+        colony.trade.insert(Edge::new_valid(a_valid, b_valid), 1.5);
+        colony.tariff.insert(a_valid, 0.1);
+        let mut scenario = Colony::default();
+        scenario.create(10.0, earth);
+        scenario.create(20.0, earth);
+
+        let mut cx = CloneContext::default().deep(true);
+        let only_a = colony.clone_entities_into(&[a], &mut scenario, &mut cx);
+        let both = colony.clone_entities_into(&[a, b], &mut scenario, &mut cx);
+
+        assert_eq!(&10.0, scenario.population.get(scenario.valid(only_a[0])));
+        assert_eq!(Some(&0.1), scenario.tariff.get(scenario.valid(both[0])));
+        assert_eq!(None, scenario.trade(only_a[0], both[1]));
+        assert_eq!(Some(1.5), scenario.trade(both[0], both[1]));
+        assert_eq!(5, scenario.alloc.len());
+    }
+}
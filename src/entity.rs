@@ -0,0 +1,231 @@
+use crate::*;
+use std::fmt::{Display, Formatter, Result};
+
+/// An arena struct that owns the allocator for its own ids, usually in an `alloc` field.
+pub trait HasAllocator: Arena + Sized {
+    fn allocator(&self) -> &Allocator<Self>;
+
+    fn allocator_mut(&mut self) -> &mut Allocator<Self>;
+
+    /// A handle to a living entity, or `None` if the id is dead.
+    fn entity(&self, id: Id<Self>) -> Option<Entity<'_, Self, Valid<'_, Id<Self>>>>
+    where
+        Self::Allocator: Validate<Self>,
+    {
+        let id = self.allocator().validate(id)?;
+        Some(Entity { arena: self, id })
+    }
+
+    /// A mutable handle to a living entity, or `None` if the id is dead.
+    fn entity_mut(&mut self, id: Id<Self>) -> Option<EntityMut<'_, Self>>
+    where
+        Self::Allocator: Validate<Self>,
+    {
+        self.allocator().validate(id)?;
+        Some(EntityMut { arena: self, id })
+    }
+}
+
+/// A valid id together with its arena.
+pub struct Entity<'a, A, I> {
+    pub arena: &'a A,
+    pub id: I,
+}
+
+impl<'a, A: Arena, I: ValidId<A>> Entity<'a, A, I> {
+    pub fn get<'c, T>(&self, component: &'c Component<A, T>) -> &'c T {
+        component.get(self.id)
+    }
+
+    pub fn get_map<'c, T>(&self, map: &'c IdMap<A, T>) -> Option<&'c T> {
+        map.get(self.id)
+    }
+
+    /// The linked id, which may be dead if the linked arena is dynamic.
+    pub fn link<B: Arena>(&self, link: &IdLink<A, B>) -> Option<Id<B>> {
+        *link.get(self.id)
+    }
+}
+
+impl<A: Arena + DisplayEntity, I: ValidId<A> + Copy> Display for Entity<'_, A, I> {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        self.arena.fmt_entity(self.id, f)
+    }
+}
+
+/// A living entity together with a mutable borrow of its arena, see [`HasAllocator::entity_mut`].
+pub struct EntityMut<'a, A: Arena> {
+    arena: &'a mut A,
+    id: Id<A>,
+}
+
+impl<'a, A: Arena> EntityMut<'a, A> {
+    pub fn id(&self) -> Valid<'_, Id<A>> {
+        Valid::new(self.id)
+    }
+
+    pub fn arena(&self) -> &A {
+        self.arena
+    }
+
+    /// Mutably borrows the entity's value in one of the arena's own components.
+    ///
+    /// The closure returns the arena's allocator along with the component, and the entity is
+    /// validated again once it returns, since the closure could have killed it. Returns `None` if
+    /// it did. The handle is consumed, so that it cannot outlive a kill by the closure.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the closure returns an allocator other than the arena's own.
+    pub fn field_mut<T, F>(self, field: F) -> Option<&'a mut T>
+    where
+        A: HasAllocator,
+        A::Allocator: Validate<A>,
+        F: FnOnce(&'a mut A) -> (&'a Allocator<A>, &'a mut Component<A, T>),
+    {
+        let expected: *const Allocator<A> = self.arena.allocator();
+        let (alloc, component) = field(self.arena);
+        assert!(
+            std::ptr::eq(alloc, expected),
+            "field_mut: the closure must return the arena's own allocator"
+        );
+
+        let id = alloc.validate(self.id)?;
+        Some(component.get_mut(id))
+    }
+
+    pub fn get<'c, T>(&self, component: &'c Component<A, T>) -> &'c T {
+        component.get(self.id())
+    }
+
+    pub fn get_mut<'c, T>(&self, component: &'c mut Component<A, T>) -> &'c mut T {
+        component.get_mut(self.id())
+    }
+
+    pub fn link<B: Arena>(&self, link: &IdLink<A, B>) -> Option<Id<B>> {
+        *link.get(self.id())
+    }
+}
+
+impl<'a, A> EntityMut<'a, A>
+where
+    A: HasAllocator + Arena<Allocator = DynamicAllocator<A>>,
+{
+    /// Kills the entity and returns its id, so that dependent storage can be updated.
+    pub fn kill(self) -> Id<A> {
+        self.arena.allocator_mut().kill(self.id);
+        self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct Ship {
+        alloc: Allocator<Self>,
+        name: Component<Self, &'static str>,
+        speed: Component<Self, f64>,
+        cargo: IdMap<Self, u32>,
+        escort: IdLink<Self, Ship>,
+    }
+
+    dynamic_arena!(Ship);
+
+    impl HasAllocator for Ship {
+        fn allocator(&self) -> &Allocator<Self> {
+            &self.alloc
+        }
+
+        fn allocator_mut(&mut self) -> &mut Allocator<Self> {
+            &mut self.alloc
+        }
+    }
+
+    impl Ship {
+        fn create(&mut self, name: &'static str, speed: f64) -> Id<Self> {
+            let id = self.alloc.create();
+            self.name.insert(id, name);
+            self.speed.insert(id, speed);
+            self.escort.insert(id, None::<Valid<Id<Self>>>);
+            id.value
+        }
+    }
+
+    #[test]
+    fn entity_gets_components() {
+        let mut ship = Ship::default();
+        let a = ship.create("Alpha", 2.0);
+        let b = ship.create("Bravo", 3.0);
+        ship.cargo.insert(ship.alloc.validate(a).unwrap(), 5);
+        ship.escort
+            .insert(ship.alloc.validate(a).unwrap(), ship.alloc.validate(b));
+
+        let entity = ship.entity(a).unwrap();
+
+        assert_eq!(&"Alpha", entity.get(&ship.name));
+        assert_eq!(Some(&5), entity.get_map(&ship.cargo));
+        assert_eq!(Some(b), entity.link(&ship.escort));
+        assert_eq!(None, ship.entity(b).unwrap().get_map(&ship.cargo));
+    }
+
+    #[test]
+    fn dead_id_has_no_entity() {
+        let mut ship = Ship::default();
+        let a = ship.create("Alpha", 2.0);
+
+        ship.entity_mut(a).unwrap().kill();
+
+        assert!(ship.entity(a).is_none());
+        assert!(ship.entity_mut(a).is_none());
+    }
+
+    #[test]
+    fn entity_mut_updates_components() {
+        let mut ship = Ship::default();
+        let a = ship.create("Alpha", 2.0);
+        let mut speed = Component::<Ship, f64>::default();
+        speed.insert(ship.alloc.validate(a).unwrap(), 0.0);
+
+        let entity = ship.entity_mut(a).unwrap();
+        *entity.get_mut(&mut speed) = 4.0;
+        *entity
+            .field_mut(|ship| (&ship.alloc, &mut ship.name))
+            .unwrap() = "Apex";
+
+        assert_eq!(&4.0, speed.get(ship.alloc.validate(a).unwrap()));
+        assert_eq!(&"Apex", ship.entity(a).unwrap().get(&ship.name));
+    }
+
+    #[test]
+    fn field_mut_revalidates_after_closure() {
+        let mut ship = Ship::default();
+        let a = ship.create("Alpha", 2.0);
+
+        let entity = ship.entity_mut(a).unwrap();
+        let name = entity.field_mut(|ship| {
+            ship.alloc.kill(a);
+            (&ship.alloc, &mut ship.name)
+        });
+
+        assert!(name.is_none());
+        assert!(ship.entity_mut(a).is_none());
+    }
+
+    #[test]
+    fn field_mut_rejects_entity_recreated_in_same_slot() {
+        let mut ship = Ship::default();
+        let a = ship.create("Alpha", 2.0);
+
+        let entity = ship.entity_mut(a).unwrap();
+        let name = entity.field_mut(|ship| {
+            ship.alloc.kill(a);
+            let b = ship.create("Bravo", 3.0);
+            assert_eq!(a.index_usize(), b.index_usize());
+            (&ship.alloc, &mut ship.name)
+        });
+
+        assert!(name.is_none());
+    }
+}
@@ -0,0 +1,97 @@
+use super::*;
+use std::any::TypeId;
+use std::fmt::Formatter;
+
+/// An id of an entity in any arena, for code that cannot name the arena at compile time.
+///
+/// The arena is identified by its `TypeId`. Use [`AnyId::downcast`] to recover the typed id.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct AnyId {
+    arena: TypeId,
+    bits: u64,
+    name: &'static str,
+}
+
+impl AnyId {
+    pub fn new<A: Arena + 'static>(id: Id<A>) -> Self {
+        Self {
+            arena: TypeId::of::<A>(),
+            bits: id.to_bits(),
+            name: A::NAME,
+        }
+    }
+
+    /// The `TypeId` of the arena that the id belongs to.
+    pub fn arena(&self) -> TypeId {
+        self.arena
+    }
+
+    /// The [`Arena::NAME`] of the arena that the id belongs to.
+    pub fn arena_name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn to_bits(&self) -> u64 {
+        self.bits
+    }
+
+    pub fn is<A: Arena + 'static>(&self) -> bool {
+        self.arena == TypeId::of::<A>()
+    }
+
+    pub fn downcast<A: Arena + 'static>(&self) -> Option<Id<A>> {
+        if self.is::<A>() {
+            Id::from_u64(self.bits)
+        } else {
+            None
+        }
+    }
+}
+
+impl<A: Arena + 'static> From<Id<A>> for AnyId {
+    fn from(id: Id<A>) -> Self {
+        Self::new(id)
+    }
+}
+
+impl Debug for AnyId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AnyId")
+            .field("arena", &self.name)
+            .field("bits", &self.bits)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::allocator::test::{FixedArena, GenerationalArena};
+
+    #[test]
+    fn downcast_to_same_arena() {
+        let id = Id::<GenerationalArena>::first(3);
+        let any = AnyId::from(id);
+
+        assert!(any.is::<GenerationalArena>());
+        assert_eq!(Some(id), any.downcast::<GenerationalArena>());
+        assert_eq!("GenerationalArena", any.arena_name());
+    }
+
+    #[test]
+    fn downcast_to_other_arena_is_none() {
+        let any = AnyId::new(Id::<GenerationalArena>::first(3));
+
+        assert!(!any.is::<FixedArena>());
+        assert_eq!(None, any.downcast::<FixedArena>());
+    }
+
+    #[test]
+    fn same_bits_in_different_arenas_are_not_equal() {
+        let fixed = AnyId::new(Id::<FixedArena>::first(0));
+        let dynamic = AnyId::new(Id::<GenerationalArena>::first(0));
+
+        assert_eq!(fixed.to_bits(), dynamic.to_bits());
+        assert_ne!(fixed, dynamic);
+    }
+}
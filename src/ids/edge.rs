@@ -3,12 +3,12 @@ use std::cmp::Ordering;
 use std::hash::{Hash, Hasher};
 
 #[derive(Debug)]
-pub struct Edge<A> {
+pub struct Edge<A: Arena> {
     pub from: Id<A>,
     pub to: Id<A>,
 }
 
-impl<A> Edge<A> {
+impl<A: Arena> Edge<A> {
     pub fn new(from: Id<A>, to: Id<A>) -> Self {
         Self { from, to }
     }
@@ -22,21 +22,21 @@ impl<A> Edge<A> {
     }
 }
 
-impl<A> PartialEq for Edge<A> {
+impl<A: Arena> PartialEq for Edge<A> {
     fn eq(&self, other: &Self) -> bool {
         self.from.eq(&other.from) && self.to.eq(&other.to)
     }
 }
 
-impl<A> Eq for Edge<A> {}
+impl<A: Arena> Eq for Edge<A> {}
 
-impl<A> PartialOrd for Edge<A> {
+impl<A: Arena> PartialOrd for Edge<A> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         self.cmp(&other).into()
     }
 }
 
-impl<A> Ord for Edge<A> {
+impl<A: Arena> Ord for Edge<A> {
     fn cmp(&self, other: &Self) -> Ordering {
         self.from
             .cmp(&other.from)
@@ -44,14 +44,14 @@ impl<A> Ord for Edge<A> {
     }
 }
 
-impl<A> Hash for Edge<A> {
+impl<A: Arena> Hash for Edge<A> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.from.hash(state);
         self.to.hash(state);
     }
 }
 
-impl<A> Clone for Edge<A> {
+impl<A: Arena> Clone for Edge<A> {
     fn clone(&self) -> Self {
         Self {
             from: self.from,
@@ -60,7 +60,14 @@ impl<A> Clone for Edge<A> {
     }
 }
 
-impl<A> Copy for Edge<A> {}
+impl<A: Arena> Copy for Edge<A> {}
+
+impl<A: Arena> StateHash for Edge<A> {
+    fn state_hash(&self, hasher: &mut StateHasher) {
+        self.from.state_hash(hasher);
+        self.to.state_hash(hasher);
+    }
+}
 
 impl<A: Arena<Allocator = DynamicAllocator<A>>> Edge<A> {
     pub fn is_alive(&self, allocator: &Allocator<A>) -> bool {
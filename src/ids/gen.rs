@@ -1,3 +1,4 @@
+use crate::IdLayout;
 use std::num::NonZeroU32;
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
@@ -14,22 +15,38 @@ impl Gen {
         NonZeroU32::new(gen).map(Self)
     }
 
-    pub fn next(&self) -> Self {
-        let gen = self.0.get().wrapping_add(1);
-        Self::new(gen).unwrap_or_default()
+    /// Returns the next generation, or `None` once the layout's generation bits are exhausted.
+    pub fn next<L: IdLayout>(&self) -> Option<Self> {
+        let gen = self.get_bits() + 1;
+
+        if gen > L::GEN_MASK {
+            None
+        } else {
+            Self::new(gen as u32)
+        }
     }
 
     pub fn get_bits(&self) -> u64 {
         self.0.get().into()
     }
+}
 
-    pub const SIZE_IN_BITS: u32 = std::mem::size_of::<Gen>() as u32 * 8;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Layout, Layout64};
 
-    pub const MASK: u64 =
-        0b_00000000_00000000_00000000_00000000_11111111_11111111_11111111_11111111;
-}
+    #[test]
+    fn next_increments() {
+        assert_eq!(Gen::new(2), Gen::default().next::<Layout64>());
+    }
 
-#[test]
-fn mask_test() {
-    assert_eq!(2u64.pow(Gen::SIZE_IN_BITS) - 1, Gen::MASK);
+    #[test]
+    fn next_is_none_at_layout_mask() {
+        type TwoBits = Layout<std::num::NonZeroU32, 2>;
+
+        let gen = Gen::new(3).unwrap();
+
+        assert_eq!(None, gen.next::<TwoBits>());
+    }
 }
@@ -1,109 +1,226 @@
 use super::*;
+use crate::__private::Sealed;
 use crate::ids::gen::Gen;
 use std::cmp::Ordering;
 use std::convert::TryFrom;
+use std::fmt::{Display, Formatter};
 use std::marker::PhantomData;
-use std::num::NonZeroU64;
+use std::str::FromStr;
+
+type Bits<A> = <<A as Arena>::Layout as IdLayout>::Bits;
 
 // #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Debug)]
-pub struct Id<A> {
-    // TODO remove old fields for SIMD optimization
-    // index: u32,
-    // gen: Gen,
-    bits: NonZeroU64,
+pub struct Id<A: Arena> {
+    bits: Bits<A>,
     marker: PhantomData<A>,
 }
 
-impl<A> Id<A> {
-    pub(crate) fn first(index: u32) -> Self {
+impl<A: Arena> Id<A> {
+    pub(crate) fn first(index: usize) -> Self {
         Self::new(index, Gen::default())
     }
 
-    pub(crate) fn new(index: u32, gen: Gen) -> Self {
-        let index_bits: u64 = u64::from(index) << Gen::SIZE_IN_BITS;
+    /// The first id of an index, or `None` if the index is out of range for the arena's layout.
+    pub(crate) fn try_first(index: usize) -> Option<Self> {
+        if index as u64 > <A::Layout as IdLayout>::MAX_INDEX {
+            return None;
+        }
+
+        Some(Self::first(index))
+    }
+
+    pub(crate) fn new(index: usize, gen: Gen) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = <A::Layout as IdLayout>::CHECK;
+        let index = index as u64;
+
+        assert!(
+            index <= <A::Layout as IdLayout>::MAX_INDEX,
+            "Index out of range: {:?}",
+            std::any::type_name::<A>()
+        );
+
+        let index_bits: u64 = index << <A::Layout as IdLayout>::GEN_BITS;
         let gen_bits: u64 = gen.get_bits();
 
-        let bits: u64 = index_bits + gen_bits;
+        let bits: u64 = index_bits | gen_bits;
 
         // UNWRAP: Gen is based on a non-zero integer, so bits will never be zero
-        let bits = NonZeroU64::new(bits).unwrap();
+        let bits = Bits::<A>::from_u64(bits).unwrap();
 
         Self::from_bits(bits)
     }
 
-    pub(crate) fn from_bits(bits: NonZeroU64) -> Self {
+    pub(crate) fn from_bits(bits: Bits<A>) -> Self {
         Self {
             bits,
             marker: PhantomData,
         }
     }
 
-    pub(crate) fn index_usize(&self) -> usize {
-        let index = self.index_u64();
+    /// The packed index and generation. The encoding only depends on the arena's layout and can
+    /// be stored or sent elsewhere and turned back into an id with `validate_bits` on the arena's
+    /// allocator.
+    pub fn to_bits(&self) -> u64 {
+        self.bits.to_u64()
+    }
 
-        usize::try_from(index).unwrap()
+    /// Unpacks bits from [`Id::to_bits`] without checking them against an allocator.
+    pub(crate) fn from_u64(bits: u64) -> Option<Self> {
+        #[allow(clippy::let_unit_value)]
+        let () = <A::Layout as IdLayout>::CHECK;
+        if bits & <A::Layout as IdLayout>::GEN_MASK == 0 {
+            return None;
+        }
+
+        Bits::<A>::from_u64(bits).map(Self::from_bits)
     }
 
-    pub(crate) fn index_u32(&self) -> u32 {
+    fn try_new(index: u64, gen: u64) -> Option<Self> {
+        if index > <A::Layout as IdLayout>::MAX_INDEX || gen > <A::Layout as IdLayout>::GEN_MASK {
+            return None;
+        }
+
+        let gen = u32::try_from(gen).ok().and_then(Gen::new)?;
+        let index = usize::try_from(index).ok()?;
+
+        Some(Self::new(index, gen))
+    }
+
+    pub(crate) fn bits(&self) -> Bits<A> {
+        self.bits
+    }
+
+    pub(crate) fn index_usize(&self) -> usize {
         let index = self.index_u64();
 
-        u32::try_from(index).unwrap()
+        usize::try_from(index).unwrap()
     }
 
     fn index_u64(&self) -> u64 {
-        self.bits.get() >> Gen::SIZE_IN_BITS
+        self.bits.to_u64() >> <A::Layout as IdLayout>::GEN_BITS
     }
 
     pub(crate) fn gen(&self) -> Gen {
-        let gen = self.bits.get() & Gen::MASK;
+        let gen = self.bits.to_u64() & <A::Layout as IdLayout>::GEN_MASK;
 
         u32::try_from(gen).ok().and_then(Gen::new).unwrap()
     }
 
-    pub(crate) fn increment(&mut self) {
-        *self = self.next_gen();
+    /// The id with the same index and the next generation, or `None` if the generation would
+    /// overflow the arena's layout.
+    pub(crate) fn next_gen(&self) -> Option<Self> {
+        let gen = self.gen().next::<A::Layout>()?;
+        Some(Self::new(self.index_usize(), gen))
     }
 
-    pub(crate) fn next_gen(&self) -> Self {
-        Self::new(self.index_u32(), self.gen().next())
+    /// The id with the same index and the first generation.
+    pub(crate) fn first_gen(&self) -> Self {
+        Self::first(self.index_usize())
+    }
+}
+
+impl<A: Arena> Debug for Id<A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Id")
+            .field("index", &self.index_u64())
+            .field("gen", &self.gen().get_bits())
+            .finish()
     }
 }
 
-impl<A> Clone for Id<A> {
+/// Formats the id as `Arena#{index}v{gen}`, e.g., `Colony#12v3`.
+impl<A: Arena> Display for Id<A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}#{}v{}",
+            A::NAME,
+            self.index_u64(),
+            self.gen().get_bits()
+        )
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ParseIdError {
+    /// The string is not of the form `Arena#{index}v{gen}`.
+    Format,
+    /// The string names a different arena.
+    Arena,
+    /// The index or generation does not fit in the arena's layout.
+    OutOfRange,
+}
+
+impl Display for ParseIdError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseIdError::Format => write!(f, "expected an id of the form Arena#{{index}}v{{gen}}"),
+            ParseIdError::Arena => write!(f, "id belongs to a different arena"),
+            ParseIdError::OutOfRange => write!(f, "id index or generation out of range"),
+        }
+    }
+}
+
+impl std::error::Error for ParseIdError {}
+
+/// Parses the `Display` form of an id, e.g., `Colony#12v3`. Only ids of dynamic arenas can be
+/// parsed, see [`Id::try_from_bits`].
+impl<A: Arena<Allocator = DynamicAllocator<A>>> FromStr for Id<A> {
+    type Err = ParseIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, rest) = s.split_once('#').ok_or(ParseIdError::Format)?;
+        let (index, gen) = rest.split_once('v').ok_or(ParseIdError::Format)?;
+
+        if name != A::NAME {
+            return Err(ParseIdError::Arena);
+        }
+
+        let index = index.parse::<u64>().map_err(|_| ParseIdError::Format)?;
+        let gen = gen.parse::<u64>().map_err(|_| ParseIdError::Format)?;
+
+        Self::try_new(index, gen).ok_or(ParseIdError::OutOfRange)
+    }
+}
+
+impl<A: Arena> Clone for Id<A> {
     fn clone(&self) -> Self {
         Self::from_bits(self.bits)
     }
 }
 
-impl<A> Copy for Id<A> {}
+impl<A: Arena> Copy for Id<A> {}
 
-impl<A> PartialEq for Id<A> {
+impl<A: Arena> PartialEq for Id<A> {
     fn eq(&self, other: &Self) -> bool {
         self.bits.eq(&other.bits)
     }
 }
 
-impl<A> Eq for Id<A> {}
+impl<A: Arena> Eq for Id<A> {}
 
-impl<A> PartialOrd for Id<A> {
+impl<A: Arena> PartialOrd for Id<A> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         self.bits.partial_cmp(&other.bits)
     }
 }
 
-impl<A> Ord for Id<A> {
+impl<A: Arena> Ord for Id<A> {
     fn cmp(&self, other: &Self) -> Ordering {
         self.bits.cmp(&other.bits)
     }
 }
 
-impl<A> Hash for Id<A> {
+impl<A: Arena> Hash for Id<A> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.bits.hash(state);
     }
 }
 
+// SAFETY: fixed ids are never reused, so an id always refers to the entity it was created for
+unsafe impl<A: Arena<Allocator = FixedAllocator<A>>> Sealed<A> for Id<A> {}
+
 impl<A: Arena<Allocator = FixedAllocator<A>>> ValidId<A> for Id<A> {
     fn index(self) -> usize {
         self.index_usize()
@@ -114,6 +231,9 @@ impl<A: Arena<Allocator = FixedAllocator<A>>> ValidId<A> for Id<A> {
     }
 }
 
+// SAFETY: as above
+unsafe impl<A: Arena<Allocator = FixedAllocator<A>>> Sealed<A> for &Id<A> {}
+
 impl<A: Arena<Allocator = FixedAllocator<A>>> ValidId<A> for &Id<A> {
     fn index(self) -> usize {
         self.index_usize()
@@ -124,7 +244,23 @@ impl<A: Arena<Allocator = FixedAllocator<A>>> ValidId<A> for &Id<A> {
     }
 }
 
+impl<A: SubArena> Id<A> {
+    /// Converts the id to an id of the parent arena.
+    pub fn upcast(self) -> Id<A::Super> {
+        Id::from_bits(self.bits)
+    }
+}
+
 impl<A: Arena<Allocator = DynamicAllocator<A>>> Id<A> {
+    /// Unpacks an id from [`Id::to_bits`]. Returns `None` if the bits cannot be an id of this
+    /// arena. The id is not known to be alive, see `DynamicAllocator::validate_bits`.
+    ///
+    /// Only ids of dynamic arenas can be unpacked, since ids of fixed arenas are valid without
+    /// an allocator. Use `FixedAllocator::validate_bits` for those.
+    pub fn try_from_bits(bits: u64) -> Option<Self> {
+        Self::from_u64(bits)
+    }
+
     pub fn is_alive(&self, allocator: &Allocator<A>) -> bool {
         allocator.is_alive(*self)
     }
@@ -134,12 +270,111 @@ impl<A: Arena<Allocator = DynamicAllocator<A>>> Id<A> {
     }
 }
 
+impl<A: Arena> StateHash for Id<A> {
+    fn state_hash(&self, hasher: &mut StateHasher) {
+        hasher.write_u64(self.to_bits());
+    }
+}
+
+impl<A: Arena> LinkId<A> for Id<A> {
+    fn link_id(self) -> Option<Id<A>> {
+        Some(self)
+    }
+}
+
+impl<A: Arena> LinkId<A> for &Id<A> {
+    fn link_id(self) -> Option<Id<A>> {
+        Some(*self)
+    }
+}
+
+impl<A: Arena> LinkId<A> for Option<Id<A>> {
+    fn link_id(self) -> Option<Id<A>> {
+        self
+    }
+}
+
+impl<A: Arena> LinkId<A> for &Option<Id<A>> {
+    fn link_id(self) -> Option<Id<A>> {
+        *self
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::allocator::test::{FixedArena, GenerationalArena};
     use std::mem::size_of;
 
+    #[derive(Debug)]
+    struct Compact;
+
+    dynamic_arena!(Compact, Layout32);
+
+    #[test]
+    fn compact_id_size() {
+        assert_eq!(4, size_of::<Id<Compact>>());
+        assert_eq!(4, size_of::<Option<Id<Compact>>>());
+    }
+
+    #[test]
+    fn index_and_gen_round_trip() {
+        let gen = Gen::new(0xFF).unwrap();
+        let id = Id::<Compact>::new(0xFF_FFFF, gen);
+
+        assert_eq!(0xFF_FFFF, id.index_usize());
+        assert_eq!(gen, id.gen());
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_out_of_range_panics() {
+        Id::<Compact>::first(1 << 24);
+    }
+
+    #[test]
+    fn bits_round_trip() {
+        let id = Id::<Compact>::new(12, Gen::new(3).unwrap());
+
+        assert_eq!((12 << 8) | 3, id.to_bits());
+        assert_eq!(Some(id), Id::try_from_bits(id.to_bits()));
+    }
+
+    #[test]
+    fn try_from_bits_rejects_invalid_bits() {
+        assert_eq!(None, Id::<Compact>::try_from_bits(0));
+        assert_eq!(None, Id::<Compact>::try_from_bits(12 << 8));
+        assert_eq!(None, Id::<Compact>::try_from_bits(1 << 32));
+    }
+
+    #[test]
+    fn display() {
+        let id = Id::<Compact>::new(12, Gen::new(3).unwrap());
+
+        assert_eq!("Compact#12v3", id.to_string());
+    }
+
+    #[test]
+    fn parse() {
+        let id = Id::<Compact>::new(12, Gen::new(3).unwrap());
+
+        assert_eq!(Ok(id), "Compact#12v3".parse());
+        assert_eq!(Ok(id), id.to_string().parse());
+    }
+
+    #[test]
+    fn parse_errors() {
+        let parse = |s: &str| s.parse::<Id<Compact>>();
+
+        assert_eq!(Err(ParseIdError::Format), parse("Compact12v3"));
+        assert_eq!(Err(ParseIdError::Format), parse("Compact#12"));
+        assert_eq!(Err(ParseIdError::Format), parse("Compact#-1v3"));
+        assert_eq!(Err(ParseIdError::Arena), parse("FixedArena#12v3"));
+        assert_eq!(Err(ParseIdError::OutOfRange), parse("Compact#12v0"));
+        assert_eq!(Err(ParseIdError::OutOfRange), parse("Compact#12v256"));
+        assert_eq!(Err(ParseIdError::OutOfRange), parse("Compact#16777216v1"));
+    }
+
     #[test]
     fn id_size() {
         let id_size = size_of::<Id<FixedArena>>();
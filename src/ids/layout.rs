@@ -0,0 +1,129 @@
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::num::{NonZeroU32, NonZeroU64};
+
+/// A non-zero integer that an `Id` is packed into. Zero is never a valid id, so `Option<Id<A>>`
+/// is the same size as `Id<A>`.
+pub trait IdBits: Copy + Eq + Ord + Hash + Debug + Send + Sync {
+    const SIZE_IN_BITS: u32;
+
+    fn from_u64(bits: u64) -> Option<Self>;
+
+    fn to_u64(self) -> u64;
+}
+
+impl IdBits for NonZeroU32 {
+    const SIZE_IN_BITS: u32 = 32;
+
+    fn from_u64(bits: u64) -> Option<Self> {
+        if bits > u64::from(u32::MAX) {
+            return None;
+        }
+
+        NonZeroU32::new(bits as u32)
+    }
+
+    fn to_u64(self) -> u64 {
+        self.get().into()
+    }
+}
+
+impl IdBits for NonZeroU64 {
+    const SIZE_IN_BITS: u32 = 64;
+
+    fn from_u64(bits: u64) -> Option<Self> {
+        NonZeroU64::new(bits)
+    }
+
+    fn to_u64(self) -> u64 {
+        self.get()
+    }
+}
+
+/// Describes how the bits of an `Id` are split between its index and its generation.
+///
+/// The generation occupies the low `GEN_BITS` bits and the index occupies the remaining high bits.
+pub trait IdLayout {
+    type Bits: IdBits;
+
+    /// The number of generation bits, between 1 and 32, and less than the size of `Bits`.
+    const GEN_BITS: u32;
+
+    const INDEX_BITS: u32 = Self::Bits::SIZE_IN_BITS.saturating_sub(Self::GEN_BITS);
+
+    const GEN_MASK: u64 = mask(Self::GEN_BITS);
+
+    const MAX_INDEX: u64 = mask(Self::INDEX_BITS);
+
+    /// Fails to compile wherever an id of the layout is made if `GEN_BITS` is out of range.
+    const CHECK: () = assert!(
+        Self::GEN_BITS >= 1 && Self::GEN_BITS <= 32 && Self::GEN_BITS < Self::Bits::SIZE_IN_BITS,
+        "GEN_BITS must be between 1 and 32, and less than the size of the id"
+    );
+}
+
+/// The lowest `bits` bits set.
+const fn mask(bits: u32) -> u64 {
+    match u64::MAX.checked_shr(64 - bits) {
+        Some(mask) if bits > 0 => mask,
+        _ => 0,
+    }
+}
+
+/// An id layout packed into `B` with `GEN_BITS` bits of generation.
+#[derive(Debug)]
+pub struct Layout<B, const GEN_BITS: u32>(PhantomData<B>);
+
+impl<B: IdBits, const GEN_BITS: u32> IdLayout for Layout<B, GEN_BITS> {
+    type Bits = B;
+    const GEN_BITS: u32 = GEN_BITS;
+}
+
+/// The default layout: a 32-bit index and a 32-bit generation.
+pub type Layout64 = Layout<NonZeroU64, 32>;
+
+/// A compact layout for memory-bound tables: a 24-bit index and an 8-bit generation.
+pub type Layout32 = Layout<NonZeroU32, 8>;
+
+/// A layout for arenas with more than 2^32 entities: a 48-bit index and a 16-bit generation.
+pub type WideLayout = Layout<NonZeroU64, 16>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layout_64() {
+        assert_eq!(32, Layout64::INDEX_BITS);
+        assert_eq!(u64::from(u32::MAX), Layout64::GEN_MASK);
+        assert_eq!(u64::from(u32::MAX), Layout64::MAX_INDEX);
+    }
+
+    #[test]
+    fn layout_32() {
+        assert_eq!(24, Layout32::INDEX_BITS);
+        assert_eq!(0xFF, Layout32::GEN_MASK);
+        assert_eq!(0xFF_FFFF, Layout32::MAX_INDEX);
+    }
+
+    #[test]
+    fn wide_layout() {
+        assert_eq!(48, WideLayout::INDEX_BITS);
+        assert_eq!(0xFFFF, WideLayout::GEN_MASK);
+    }
+
+    #[test]
+    fn mask_handles_empty_and_full_widths() {
+        assert_eq!(0, mask(0));
+        assert_eq!(0xFF, mask(8));
+        assert_eq!(u64::MAX, mask(64));
+    }
+
+    #[test]
+    fn non_zero_u32_rejects_out_of_range_bits() {
+        assert_eq!(None, NonZeroU32::from_u64(0));
+        assert_eq!(None, NonZeroU32::from_u64(1 << 32));
+        assert_eq!(Some(1), NonZeroU32::from_u64(1).map(NonZeroU32::get));
+    }
+}
@@ -2,11 +2,15 @@ use crate::*;
 use std::fmt::Debug;
 use std::hash::{Hash, Hasher};
 
+pub use any::*;
 pub use edge::*;
 pub use id::*;
+pub use layout::*;
 pub use valid::*;
 
+mod any;
 mod edge;
 mod gen;
 mod id;
+mod layout;
 mod valid;
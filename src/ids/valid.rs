@@ -1,4 +1,5 @@
-use crate::{ContextualIterator, Id, ValidId};
+use crate::__private::Sealed;
+use crate::{Arena, ContextualIterator, Id, LinkId, SubArena, ValidId};
 use std::marker::PhantomData;
 
 /// A wrapper that is used show that an Id or collection of Ids are valid for the specified lifetime.
@@ -7,7 +8,7 @@ use std::marker::PhantomData;
 /// 'a - The lifetime that the given wrapper is valid.
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
 pub struct Valid<'a, T> {
-    pub value: T,
+    pub(crate) value: T,
     marker: PhantomData<&'a T>,
 }
 
@@ -19,9 +20,45 @@ impl<'a, T> Valid<'a, T> {
         }
     }
 
-    pub fn assert(value: T) -> Self {
+    /// Wraps a value without checking it against an allocator. Prefer validating ids through
+    /// an allocator or a [`ValidToken`].
+    ///
+    /// # Safety
+    ///
+    /// Every id within `value` must refer to a living entity for all of `'a`. Indexing
+    /// components with a dead id returns the data of whichever entity reused its slot.
+    ///
+    /// [`ValidToken`]: crate::ValidToken
+    pub unsafe fn assume_valid(value: T) -> Self {
         Self::new(value)
     }
+
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// Derives a value that is valid for the same lifetime, such as a row built from valid ids.
+    ///
+    /// # Safety
+    ///
+    /// Every id within the result must come from `value`, since the closure could otherwise
+    /// replace a valid id with an unchecked one.
+    pub unsafe fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Valid<'a, U> {
+        Valid::new(f(self.value))
+    }
+
+    /// Derives a value that is valid for the same lifetime, such as an id stored within a row.
+    ///
+    /// # Safety
+    ///
+    /// As for [`Valid::map`].
+    pub unsafe fn map_ref<U, F: FnOnce(&T) -> U>(&self, f: F) -> Valid<'a, U> {
+        Valid::new(f(&self.value))
+    }
 }
 
 impl<'a, T: Copy> Valid<'a, &T> {
@@ -30,7 +67,17 @@ impl<'a, T: Copy> Valid<'a, &T> {
     }
 }
 
-impl<A> ValidId<A> for Valid<'_, Id<A>> {
+impl<'a, A: SubArena> Valid<'a, Id<A>> {
+    /// Converts the id to a valid id of the parent arena.
+    pub fn upcast(self) -> Valid<'a, Id<A::Super>> {
+        Valid::new(self.value.upcast())
+    }
+}
+
+// SAFETY: `Valid` can only be constructed by checking the id, or with `assume_valid`
+unsafe impl<A: Arena> Sealed<A> for Valid<'_, Id<A>> {}
+
+impl<A: Arena> ValidId<A> for Valid<'_, Id<A>> {
     fn index(self) -> usize {
         self.value.index_usize()
     }
@@ -40,7 +87,10 @@ impl<A> ValidId<A> for Valid<'_, Id<A>> {
     }
 }
 
-impl<'a, A> ValidId<A> for Valid<'_, &'a Id<A>> {
+// SAFETY: as above
+unsafe impl<A: Arena> Sealed<A> for Valid<'_, &Id<A>> {}
+
+impl<'a, A: Arena> ValidId<A> for Valid<'_, &'a Id<A>> {
     fn index(self) -> usize {
         self.value.index_usize()
     }
@@ -50,7 +100,19 @@ impl<'a, A> ValidId<A> for Valid<'_, &'a Id<A>> {
     }
 }
 
-impl<'a, C, ID> IntoIterator for Valid<'a, iter_context::Iter<'a, C, Id<ID>>> {
+impl<A: Arena> LinkId<A> for Valid<'_, &Id<A>> {
+    fn link_id(self) -> Option<Id<A>> {
+        Some(*self.value)
+    }
+}
+
+impl<A: Arena> LinkId<A> for Option<Valid<'_, &Id<A>>> {
+    fn link_id(self) -> Option<Id<A>> {
+        self.map(|id| *id.value)
+    }
+}
+
+impl<'a, C, ID: Arena> IntoIterator for Valid<'a, iter_context::Iter<'a, C, Id<ID>>> {
     type Item = Valid<'a, &'a Id<ID>>;
     type IntoIter = Iter<'a, Id<ID>>;
 
@@ -61,11 +123,11 @@ impl<'a, C, ID> IntoIterator for Valid<'a, iter_context::Iter<'a, C, Id<ID>>> {
     }
 }
 
-impl<'a, C, ID> ContextualIterator for Valid<'a, iter_context::Iter<'a, C, Id<ID>>> {
+impl<'a, C, ID: Arena> ContextualIterator for Valid<'a, iter_context::Iter<'a, C, Id<ID>>> {
     type Context = C;
 }
 
-impl<'a, C, ID> IntoIterator for Valid<'a, iter_context::Iter<'a, C, Option<Id<ID>>>> {
+impl<'a, C, ID: Arena> IntoIterator for Valid<'a, iter_context::Iter<'a, C, Option<Id<ID>>>> {
     type Item = Option<Valid<'a, &'a Id<ID>>>;
     type IntoIter = Iter<'a, Option<Id<ID>>>;
 
@@ -76,7 +138,7 @@ impl<'a, C, ID> IntoIterator for Valid<'a, iter_context::Iter<'a, C, Option<Id<I
     }
 }
 
-impl<'a, C, ID> ContextualIterator for Valid<'a, iter_context::Iter<'a, C, Option<Id<ID>>>> {
+impl<'a, C, ID: Arena> ContextualIterator for Valid<'a, iter_context::Iter<'a, C, Option<Id<ID>>>> {
     type Context = C;
 }
 
@@ -84,7 +146,7 @@ pub struct Iter<'a, ID> {
     iter: std::slice::Iter<'a, ID>,
 }
 
-impl<'a, ID> Iterator for Iter<'a, Id<ID>> {
+impl<'a, ID: Arena> Iterator for Iter<'a, Id<ID>> {
     type Item = Valid<'a, &'a Id<ID>>;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -92,7 +154,7 @@ impl<'a, ID> Iterator for Iter<'a, Id<ID>> {
     }
 }
 
-impl<'a, ID> Iterator for Iter<'a, Option<Id<ID>>> {
+impl<'a, ID: Arena> Iterator for Iter<'a, Option<Id<ID>>> {
     type Item = Option<Valid<'a, &'a Id<ID>>>;
 
     fn next(&mut self) -> Option<Self::Item> {
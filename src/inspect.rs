@@ -0,0 +1,286 @@
+use crate::*;
+use std::fmt::{Debug, Display, Formatter, Result};
+
+/// An arena that lists its components by name so that they can be inspected generically.
+pub trait Reflect: Arena + Sized {
+    fn reflect<'a>(&'a self, fields: &mut Fields<'a, Self>);
+}
+
+/// The named components of an arena, see [`Reflect`].
+pub struct Fields<'a, A: Arena> {
+    fields: Vec<Field<'a, A>>,
+}
+
+struct Field<'a, A: Arena> {
+    name: &'static str,
+    type_name: &'static str,
+    get: Box<dyn Fn(Id<A>) -> Option<&'a dyn Debug> + 'a>,
+}
+
+impl<'a, A: Arena> Fields<'a, A> {
+    pub fn component<T: Debug + 'a>(
+        &mut self,
+        name: &'static str,
+        component: &'a Component<A, T>,
+    ) -> &mut Self {
+        self.field(name, move |id| {
+            let id = Valid::new(id);
+            if id.index() < component.len() {
+                Some(component.get(id))
+            } else {
+                None
+            }
+        })
+    }
+
+    pub fn chunked<T: Debug + 'a, const N: usize>(
+        &mut self,
+        name: &'static str,
+        component: &'a ChunkedComponent<A, T, N>,
+    ) -> &mut Self {
+        self.field(name, move |id| {
+            let id = Valid::new(id);
+            if id.index() < component.len() {
+                Some(component.get(id))
+            } else {
+                None
+            }
+        })
+    }
+
+    pub fn map<T: Debug + 'a>(&mut self, name: &'static str, map: &'a IdMap<A, T>) -> &mut Self {
+        self.field(name, move |id| map.get(Valid::new(id)))
+    }
+
+    fn field<T: Debug + 'a, F>(&mut self, name: &'static str, get: F) -> &mut Self
+    where
+        F: Fn(Id<A>) -> Option<&'a T> + 'a,
+    {
+        self.fields.push(Field {
+            name,
+            type_name: std::any::type_name::<T>(),
+            get: Box::new(move |id| get(id).map(|value| value as &dyn Debug)),
+        });
+        self
+    }
+}
+
+/// Prints the reflected components of an arena, for debugging and test snapshots.
+pub struct Inspector<'a, A: Arena> {
+    alloc: &'a Allocator<A>,
+    fields: Vec<Field<'a, A>>,
+}
+
+impl<'a, A> Inspector<'a, A>
+where
+    A: Reflect,
+    A::Allocator: Validate<A>,
+{
+    pub fn new(arena: &'a A, alloc: &'a Allocator<A>) -> Self {
+        let mut fields = Fields { fields: vec![] };
+        arena.reflect(&mut fields);
+
+        Self {
+            alloc,
+            fields: fields.fields,
+        }
+    }
+
+    /// The name and type name of each reflected component.
+    pub fn fields(&self) -> Vec<(&'static str, &'static str)> {
+        self.fields.iter().map(|f| (f.name, f.type_name)).collect()
+    }
+
+    /// Displays every component value of a single entity, one per line.
+    pub fn entity<I: ValidId<A>>(&self, id: I) -> EntityView<'_, 'a, A> {
+        EntityView {
+            inspector: self,
+            id: id.id(),
+        }
+    }
+
+    /// Displays a table with one row for each living entity in `ids`. Dead ids are skipped.
+    pub fn table<I: LinkId<A>>(&self, ids: impl IntoIterator<Item = I>) -> TableView<'_, 'a, A> {
+        let ids = ids
+            .into_iter()
+            .filter_map(|id| id.link_id())
+            .filter(|id| self.alloc.validate(*id).is_some())
+            .collect();
+
+        TableView {
+            inspector: self,
+            ids,
+        }
+    }
+
+    fn values(&self, id: Id<A>) -> Vec<String> {
+        self.fields
+            .iter()
+            .map(|field| match (field.get)(id) {
+                Some(value) => format!("{:?}", value),
+                None => String::from("-"),
+            })
+            .collect()
+    }
+}
+
+pub struct EntityView<'i, 'a, A: Arena> {
+    inspector: &'i Inspector<'a, A>,
+    id: Id<A>,
+}
+
+impl<A> Display for EntityView<'_, '_, A>
+where
+    A: Reflect,
+    A::Allocator: Validate<A>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}", self.id)?;
+
+        let names = self.inspector.fields.iter().map(|field| field.name);
+        for (name, value) in names.zip(self.inspector.values(self.id)) {
+            write!(f, "\n  {}: {}", name, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct TableView<'i, 'a, A: Arena> {
+    inspector: &'i Inspector<'a, A>,
+    ids: Vec<Id<A>>,
+}
+
+impl<A> Display for TableView<'_, '_, A>
+where
+    A: Reflect,
+    A::Allocator: Validate<A>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let header = std::iter::once(String::from("id"))
+            .chain(self.inspector.fields.iter().map(|f| f.name.to_string()))
+            .collect::<Vec<_>>();
+
+        let rows = self
+            .ids
+            .iter()
+            .map(|id| {
+                std::iter::once(id.to_string())
+                    .chain(self.inspector.values(*id))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut widths = header.iter().map(String::len).collect::<Vec<_>>();
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.len());
+            }
+        }
+
+        for (i, row) in std::iter::once(&header).chain(&rows).enumerate() {
+            if i != 0 {
+                writeln!(f)?;
+            }
+
+            let cells = row.iter().zip(&widths);
+            let line = cells
+                .map(|(cell, width)| format!("{:width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join(" | ");
+
+            write!(f, "{}", line.trim_end())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct Ship {
+        alloc: Allocator<Self>,
+        name: Component<Self, String>,
+        speed: Component<Self, f64>,
+        cargo: IdMap<Self, u32>,
+    }
+
+    dynamic_arena!(Ship);
+
+    impl Reflect for Ship {
+        fn reflect<'a>(&'a self, fields: &mut Fields<'a, Self>) {
+            fields
+                .component("name", &self.name)
+                .component("speed", &self.speed)
+                .map("cargo", &self.cargo);
+        }
+    }
+
+    impl Ship {
+        fn create(&mut self, name: &str, speed: f64) -> Id<Self> {
+            let id = self.alloc.create();
+            self.name.insert(id, name.to_string());
+            self.speed.insert(id, speed);
+            id.value
+        }
+    }
+
+    fn ships() -> Ship {
+        let mut ship = Ship::default();
+
+        let a = ship.create("Alpha", 1.5);
+        ship.create("Bravo", 20.0);
+        let c = ship.create("Charlie", 300.0);
+
+        let a = ship.alloc.validate(a).unwrap();
+        ship.cargo.insert(a, 12);
+        ship.alloc.kill(c);
+
+        ship
+    }
+
+    #[test]
+    fn fields() {
+        let ship = ships();
+        let inspector = Inspector::new(&ship, &ship.alloc);
+
+        let fields = inspector.fields();
+
+        assert_eq!(
+            vec![
+                ("name", std::any::type_name::<String>()),
+                ("speed", "f64"),
+                ("cargo", "u32")
+            ],
+            fields
+        );
+    }
+
+    #[test]
+    fn entity() {
+        let ship = ships();
+        let inspector = Inspector::new(&ship, &ship.alloc);
+        let id = ship.alloc.ids().next().unwrap().unwrap();
+
+        let expected = "Ship#0v1\n  name: \"Alpha\"\n  speed: 1.5\n  cargo: 12";
+
+        assert_eq!(expected, inspector.entity(id).to_string());
+    }
+
+    #[test]
+    fn table_skips_dead_ids() {
+        let ship = ships();
+        let inspector = Inspector::new(&ship, &ship.alloc);
+        let ids = ship.alloc.ids();
+
+        let expected = "\
+id       | name    | speed | cargo
+Ship#0v1 | \"Alpha\" | 1.5   | 12
+Ship#1v1 | \"Bravo\" | 20.0  | -";
+
+        assert_eq!(expected, inspector.table(ids).to_string());
+    }
+}
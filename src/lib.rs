@@ -1,11 +1,19 @@
 pub use allocator::*;
 pub use arena::*;
+pub use clone::*;
+pub use entity::*;
 use fnv::FnvHashMap as HashMap;
 pub use ids::*;
+pub use inspect::*;
 use iter_context::*;
+pub use prefab::*;
+pub use registry::*;
+pub use schedule::*;
+pub use state_hash::*;
 pub use storage::*;
 pub use tables::*;
 pub use traits::*;
+pub use world::*;
 
 // #[cfg(feature = "serde")]
 // use serde::{Deserialize, Serialize};
@@ -15,7 +23,16 @@ use rayon::prelude::*;
 
 mod allocator;
 mod arena;
+mod cascade;
+mod clone;
+mod entity;
 mod ids;
+mod inspect;
+mod prefab;
+mod registry;
+mod schedule;
+mod state_hash;
 mod storage;
 mod tables;
 mod traits;
+mod world;
@@ -0,0 +1,233 @@
+/// A bundle of component values that spawns one or more linked entities into `S`.
+///
+/// Usually generated by `prefab!`, along with the struct of spawned ids.
+pub trait Prefab<S> {
+    /// The id that the spawned entities link to, such as the planet that a moon orbits.
+    type Parent;
+
+    /// The ids of every entity spawned by the bundle.
+    type Ids;
+
+    fn spawn(self, state: &mut S, parent: Self::Parent) -> Self::Ids;
+}
+
+/// Generates a [`Prefab`] bundle, and a struct of the ids that it spawns.
+///
+/// The bundle holds a root row, spawned by the given closure, and a `Vec` of each child
+/// bundle. Children are spawned after the root and receive its id as their parent.
+///
+/// ```ignore
+/// prefab! {
+///     pub struct Planet {
+///         type State = State;
+///         type Parent = Id<System>;
+///         type Ids = struct PlanetIds;
+///
+///         body: BodyRow => Id<Body> = |state, row, system| state.body.create(row, system);
+///
+///         children {
+///             moons: Moon,
+///         }
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! prefab {
+    (
+        $vis:vis struct $prefab:ident {
+            type State = $state:ty;
+            type Parent = $parent:ty;
+            type Ids = struct $ids:ident;
+
+            $root:ident: $row:ty => $id:ty = |$s:ident, $r:ident, $p:ident| $spawn:expr;
+
+            $(
+                children {
+                    $( $child:ident: $child_prefab:ty, )*
+                }
+            )?
+        }
+    ) => {
+        #[derive(Debug, Clone)]
+        $vis struct $prefab {
+            pub $root: $row,
+            $( $(
+                pub $child: Vec<$child_prefab>,
+            )* )?
+        }
+
+        #[derive(Debug, Clone)]
+        $vis struct $ids {
+            pub $root: $id,
+            $( $(
+                pub $child: Vec<<$child_prefab as $crate::Prefab<$state>>::Ids>,
+            )* )?
+        }
+
+        impl $crate::Prefab<$state> for $prefab {
+            type Parent = $parent;
+            type Ids = $ids;
+
+            fn spawn(self, $s: &mut $state, $p: $parent) -> $ids {
+                let $r = self.$root;
+                let root: $id = $spawn;
+
+                $ids {
+                    $root: root,
+                    $( $(
+                        $child: self
+                            .$child
+                            .into_iter()
+                            .map(|child| $crate::Prefab::spawn(child, $s, root))
+                            .collect(),
+                    )* )?
+                }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[derive(Debug, Default)]
+    struct System {
+        alloc: Allocator<Self>,
+        name: Component<Self, &'static str>,
+    }
+
+    fixed_arena!(System);
+
+    #[derive(Debug, Default)]
+    struct Body {
+        alloc: Allocator<Self>,
+        name: Component<Self, &'static str>,
+        system: Component<Self, Id<System>>,
+        parent: Component<Self, Option<Id<Body>>>,
+    }
+
+    fixed_arena!(Body);
+
+    impl Body {
+        fn create(
+            &mut self,
+            name: &'static str,
+            system: Id<System>,
+            parent: Option<Id<Body>>,
+        ) -> Id<Self> {
+            let id = self.alloc.create();
+            self.name.insert(id, name);
+            self.system.insert(id, system);
+            self.parent.insert(id, parent);
+            id
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct State {
+        system: System,
+        body: Body,
+    }
+
+    prefab! {
+        struct Star {
+            type State = State;
+            type Parent = ();
+            type Ids = struct StarIds;
+
+            system: &'static str => Id<System> = |state, name, _parent| {
+                let id = state.system.alloc.create();
+                state.system.name.insert(id, name);
+                id
+            };
+
+            children {
+                planets: Planet,
+            }
+        }
+    }
+
+    prefab! {
+        struct Planet {
+            type State = State;
+            type Parent = Id<System>;
+            type Ids = struct PlanetIds;
+
+            body: &'static str => Id<Body> = |state, name, system| {
+                state.body.create(name, system, None)
+            };
+
+            children {
+                moons: Moon,
+            }
+        }
+    }
+
+    prefab! {
+        struct Moon {
+            type State = State;
+            type Parent = Id<Body>;
+            type Ids = struct MoonIds;
+
+            body: &'static str => Id<Body> = |state, name, planet| {
+                let system = *state.body.system.get(planet);
+                state.body.create(name, system, Some(planet))
+            };
+        }
+    }
+
+    fn sol() -> Star {
+        let moon = |body| Moon { body };
+
+        Star {
+            system: "Sol",
+            planets: vec![
+                Planet {
+                    body: "Earth",
+                    moons: vec![moon("Luna")],
+                },
+                Planet {
+                    body: "Mars",
+                    moons: vec![moon("Phobos"), moon("Deimos")],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn spawn_returns_tree_of_ids() {
+        let mut state = State::default();
+
+        let ids = sol().spawn(&mut state, ());
+
+        assert_eq!(&"Sol", state.system.name.get(ids.system));
+        assert_eq!(2, ids.planets.len());
+
+        let mars = &ids.planets[1];
+        assert_eq!(&"Mars", state.body.name.get(mars.body));
+        let names: Vec<_> = mars
+            .moons
+            .iter()
+            .map(|m| *state.body.name.get(m.body))
+            .collect();
+        assert_eq!(vec!["Phobos", "Deimos"], names);
+    }
+
+    #[test]
+    fn children_link_to_parent() {
+        let mut state = State::default();
+
+        let ids = sol().spawn(&mut state, ());
+
+        for planet in &ids.planets {
+            assert_eq!(&ids.system, state.body.system.get(planet.body));
+            assert_eq!(&None, state.body.parent.get(planet.body));
+
+            for moon in &planet.moons {
+                assert_eq!(&ids.system, state.body.system.get(moon.body));
+                assert_eq!(&Some(planet.body), state.body.parent.get(moon.body));
+            }
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use crate::*;
+use std::any::TypeId;
+use std::fmt::{Display, Formatter, Result};
+
+/// A set of arenas that can be queried with an [`AnyId`] without knowing its arena type.
+#[derive(Default)]
+pub struct ArenaRegistry<'a> {
+    arenas: HashMap<TypeId, Box<dyn ErasedArena + 'a>>,
+}
+
+impl<'a> ArenaRegistry<'a> {
+    /// Adds an arena to the registry, replacing any previous registration of the same arena.
+    pub fn register<A>(&mut self, arena: &'a A, alloc: &'a Allocator<A>)
+    where
+        A: DisplayEntity + 'static,
+        A::Allocator: Validate<A>,
+    {
+        self.arenas
+            .insert(TypeId::of::<A>(), Box::new(Registered { arena, alloc }));
+    }
+
+    pub fn contains(&self, arena: TypeId) -> bool {
+        self.arenas.contains_key(&arena)
+    }
+
+    /// Returns false if the id is dead or its arena has not been registered.
+    pub fn is_alive(&self, id: AnyId) -> bool {
+        self.arenas
+            .get(&id.arena())
+            .map(|arena| arena.is_alive(id.to_bits()))
+            .unwrap_or(false)
+    }
+
+    /// Formats a living entity using its arena's `DisplayEntity` implementation.
+    pub fn display(&self, id: AnyId) -> Option<AnyEntity<'_>> {
+        let arena = self.arenas.get(&id.arena())?;
+
+        if arena.is_alive(id.to_bits()) {
+            Some(AnyEntity {
+                arena: arena.as_ref(),
+                bits: id.to_bits(),
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl std::fmt::Debug for ArenaRegistry<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.debug_set()
+            .entries(self.arenas.values().map(|arena| arena.name()))
+            .finish()
+    }
+}
+
+/// A living entity of any arena, see [`ArenaRegistry::display`].
+pub struct AnyEntity<'a> {
+    arena: &'a dyn ErasedArena,
+    bits: u64,
+}
+
+impl Display for AnyEntity<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        self.arena.fmt_entity(self.bits, f)
+    }
+}
+
+trait ErasedArena {
+    fn name(&self) -> &'static str;
+
+    fn is_alive(&self, bits: u64) -> bool;
+
+    fn fmt_entity(&self, bits: u64, f: &mut Formatter<'_>) -> Result;
+}
+
+struct Registered<'a, A: Arena> {
+    arena: &'a A,
+    alloc: &'a Allocator<A>,
+}
+
+impl<A> ErasedArena for Registered<'_, A>
+where
+    A: DisplayEntity + 'static,
+    A::Allocator: Validate<A>,
+{
+    fn name(&self) -> &'static str {
+        A::NAME
+    }
+
+    fn is_alive(&self, bits: u64) -> bool {
+        Id::<A>::from_u64(bits)
+            .and_then(|id| self.alloc.validate(id))
+            .is_some()
+    }
+
+    fn fmt_entity(&self, bits: u64, f: &mut Formatter<'_>) -> Result {
+        match Id::<A>::from_u64(bits).and_then(|id| self.alloc.validate(id)) {
+            Some(id) => self.arena.fmt_entity(id, f),
+            None => Err(std::fmt::Error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct Planet {
+        alloc: Allocator<Self>,
+        name: Component<Self, &'static str>,
+    }
+
+    fixed_arena!(Planet);
+
+    impl DisplayEntity for Planet {
+        fn fmt_entity<I: ValidId<Self>>(&self, id: I, f: &mut Formatter) -> Result {
+            write!(f, "Planet {}", self.name.get(id))
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct Ship {
+        alloc: Allocator<Self>,
+        speed: Component<Self, f64>,
+    }
+
+    dynamic_arena!(Ship);
+
+    impl DisplayEntity for Ship {
+        fn fmt_entity<I: ValidId<Self>>(&self, id: I, f: &mut Formatter) -> Result {
+            write!(f, "Ship at {} m/s", self.speed.get(id))
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct Unregistered;
+
+    fixed_arena!(Unregistered);
+
+    #[test]
+    fn display_entities_of_different_arenas() {
+        let mut planet = Planet::default();
+        let earth = planet.alloc.create();
+        planet.name.insert(earth, "Earth");
+
+        let mut ship = Ship::default();
+        let enterprise = ship.alloc.create();
+        ship.speed.insert(enterprise, 12.5);
+        let enterprise = enterprise.value;
+
+        let mut registry = ArenaRegistry::default();
+        registry.register(&planet, &planet.alloc);
+        registry.register(&ship, &ship.alloc);
+
+        let ids = [AnyId::new(earth), AnyId::new(enterprise)];
+        let names: Vec<String> = ids
+            .iter()
+            .filter_map(|id| registry.display(*id))
+            .map(|entity| entity.to_string())
+            .collect();
+
+        assert_eq!(vec!["Planet Earth", "Ship at 12.5 m/s"], names);
+    }
+
+    #[test]
+    fn dead_entity_is_not_alive() {
+        let mut ship = Ship::default();
+        let id = ship.alloc.create();
+        ship.speed.insert(id, 1.0);
+        let id = id.value;
+        ship.alloc.kill(id);
+
+        let mut registry = ArenaRegistry::default();
+        registry.register(&ship, &ship.alloc);
+
+        assert!(!registry.is_alive(AnyId::new(id)));
+        assert!(registry.display(AnyId::new(id)).is_none());
+    }
+
+    #[test]
+    fn unregistered_arena_is_not_alive() {
+        let mut alloc = Allocator::<Unregistered>::default();
+        let id = AnyId::new(alloc.create());
+
+        let registry = ArenaRegistry::default();
+
+        assert!(!registry.contains(id.arena()));
+        assert!(!registry.is_alive(id));
+        assert!(registry.display(id).is_none());
+    }
+}
@@ -0,0 +1,464 @@
+use crate::*;
+use std::any::TypeId;
+use std::fmt::{Display, Formatter};
+
+/// The resources of a [`World`] that a system reads and writes.
+#[derive(Debug, Default, Clone)]
+pub struct Access {
+    resources: Vec<(TypeId, &'static str, Mode)>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Mode {
+    Read,
+    Write,
+}
+
+impl Access {
+    pub fn read<T: 'static>(mut self) -> Self {
+        self.push::<T>(Mode::Read);
+        self
+    }
+
+    pub fn write<T: 'static>(mut self) -> Self {
+        self.push::<T>(Mode::Write);
+        self
+    }
+
+    fn push<T: 'static>(&mut self, mode: Mode) {
+        let name = std::any::type_name::<T>();
+        self.resources.push((TypeId::of::<T>(), name, mode));
+    }
+
+    fn mode(&self, id: TypeId) -> Option<Mode> {
+        self.resources
+            .iter()
+            .find(|(t, _, _)| *t == id)
+            .map(|(_, _, mode)| *mode)
+    }
+
+    /// The first resource that one system writes while the other reads or writes it.
+    pub fn conflict(&self, other: &Access) -> Option<&'static str> {
+        self.resources
+            .iter()
+            .find_map(|(id, name, mode)| match (*mode, other.mode(*id)?) {
+                (Mode::Read, Mode::Read) => None,
+                _ => Some(*name),
+            })
+    }
+
+    /// A resource declared more than once, which is ambiguous if the modes differ.
+    fn duplicate(&self) -> Option<&'static str> {
+        self.resources
+            .iter()
+            .enumerate()
+            .find(|(i, (id, _, _))| self.resources[..*i].iter().any(|(t, _, _)| t == id))
+            .map(|(_, (_, name, _))| *name)
+    }
+}
+
+/// Deferred changes to a [`World`], applied at the sync point after a batch of systems.
+#[derive(Default)]
+pub struct Commands {
+    commands: Vec<Command>,
+}
+
+type Command = Box<dyn FnOnce(&mut World) + Send>;
+
+impl Commands {
+    pub fn push<F: FnOnce(&mut World) + Send + 'static>(&mut self, command: F) {
+        self.commands.push(Box::new(command));
+    }
+
+    pub fn insert<T: Send + Sync + 'static>(&mut self, value: T) {
+        self.push(move |world| world.insert(value));
+    }
+
+    /// Kills the entity and runs the kill hooks of its arena, see [`World::kill`].
+    ///
+    /// Nothing is borrowed when commands are applied, so the command only panics if the arena or
+    /// a resource that a hook needs is missing.
+    pub fn kill<A>(&mut self, id: Id<A>)
+    where
+        A: HasAllocator + Arena<Allocator = DynamicAllocator<A>> + Send + 'static,
+    {
+        self.push(move |world| {
+            if let Err(e) = world.kill(id) {
+                panic!("{}", e);
+            }
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    fn apply(self, world: &mut World) {
+        for command in self.commands {
+            command(world);
+        }
+    }
+}
+
+impl std::fmt::Debug for Commands {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Commands")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+/// The world as seen by a running system, limited to the resources in its [`Access`].
+pub struct SystemContext<'a> {
+    world: &'a World,
+    system: &'static str,
+    access: &'a Access,
+    pub commands: Commands,
+}
+
+impl<'a> SystemContext<'a> {
+    /// Panics if the system did not declare the resource.
+    pub fn get<T: 'static>(&self) -> Res<'a, T> {
+        if self.access.mode(TypeId::of::<T>()).is_none() {
+            self.undeclared::<T>();
+        }
+
+        self.world.get()
+    }
+
+    /// Panics if the system did not declare the resource as written.
+    pub fn get_mut<T: 'static>(&self) -> ResMut<'a, T> {
+        if self.access.mode(TypeId::of::<T>()) != Some(Mode::Write) {
+            self.undeclared::<T>();
+        }
+
+        self.world.get_mut()
+    }
+
+    fn undeclared<T>(&self) -> ! {
+        panic!(
+            "{} did not declare access to {}",
+            self.system,
+            std::any::type_name::<T>()
+        )
+    }
+}
+
+type SystemFn = Box<dyn Fn(&mut SystemContext) + Send + Sync>;
+
+struct System {
+    name: &'static str,
+    access: Access,
+    run: SystemFn,
+}
+
+/// Runs systems in batches that do not conflict, in parallel with the `rayon` feature.
+///
+/// Each system runs after every earlier system that conflicts with it, so every system sees the
+/// resources that it declares as if the systems had run in registration order.
+///
+/// Commands are applied at the end of each batch, in the registration order of the systems in
+/// that batch. A system does not see the commands of earlier systems in its own batch, and the
+/// commands of a later system can be applied before those of an earlier system in a later batch.
+#[derive(Default)]
+pub struct Schedule {
+    systems: Vec<System>,
+    batches: Vec<Vec<usize>>,
+    sync: usize,
+}
+
+impl Schedule {
+    /// Adds a system and returns the index of the batch that it will run in.
+    pub fn add_system<F>(
+        &mut self,
+        name: &'static str,
+        access: Access,
+        run: F,
+    ) -> Result<usize, ScheduleError>
+    where
+        F: Fn(&mut SystemContext) + Send + Sync + 'static,
+    {
+        if self.systems.iter().any(|system| system.name == name) {
+            return Err(ScheduleError::DuplicateSystem(name));
+        }
+
+        if let Some(resource) = access.duplicate() {
+            return Err(ScheduleError::DuplicateAccess {
+                system: name,
+                resource,
+            });
+        }
+
+        let after = self.batches[self.sync..]
+            .iter()
+            .rposition(|batch| {
+                batch
+                    .iter()
+                    .any(|i| self.systems[*i].access.conflict(&access).is_some())
+            })
+            .map(|i| self.sync + i + 1)
+            .unwrap_or(self.sync);
+
+        if after == self.batches.len() {
+            self.batches.push(vec![]);
+        }
+
+        self.batches[after].push(self.systems.len());
+        self.systems.push(System {
+            name,
+            access,
+            run: Box::new(run),
+        });
+
+        Ok(after)
+    }
+
+    /// Systems added after the sync point run after every system added before it.
+    pub fn add_sync(&mut self) {
+        self.sync = self.batches.len();
+    }
+
+    /// The names of the systems in each batch.
+    pub fn batches(&self) -> Vec<Vec<&'static str>> {
+        self.batches
+            .iter()
+            .map(|batch| batch.iter().map(|i| self.systems[*i].name).collect())
+            .collect()
+    }
+
+    /// The earlier systems that a system waits for, and the resource each conflicts on.
+    pub fn dependencies(&self, name: &str) -> Vec<(&'static str, &'static str)> {
+        let index = match self.systems.iter().position(|s| s.name == name) {
+            Some(index) => index,
+            None => return vec![],
+        };
+
+        let system = &self.systems[index];
+        self.systems[..index]
+            .iter()
+            .filter_map(|other| Some((other.name, other.access.conflict(&system.access)?)))
+            .collect()
+    }
+
+    pub fn run(&self, world: &mut World) {
+        for batch in &self.batches {
+            for commands in self.run_batch(batch, world) {
+                commands.apply(world);
+            }
+        }
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    fn run_batch(&self, batch: &[usize], world: &World) -> Vec<Commands> {
+        batch
+            .iter()
+            .map(|i| Self::run_system(&self.systems[*i], world))
+            .collect()
+    }
+
+    #[cfg(feature = "rayon")]
+    fn run_batch(&self, batch: &[usize], world: &World) -> Vec<Commands> {
+        batch
+            .par_iter()
+            .map(|i| Self::run_system(&self.systems[*i], world))
+            .collect()
+    }
+
+    fn run_system(system: &System, world: &World) -> Commands {
+        let mut context = SystemContext {
+            world,
+            system: system.name,
+            access: &system.access,
+            commands: Commands::default(),
+        };
+
+        (system.run)(&mut context);
+
+        context.commands
+    }
+}
+
+impl std::fmt::Debug for Schedule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.batches()).finish()
+    }
+}
+
+/// The reason a system could not be added to a [`Schedule`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ScheduleError {
+    /// A system with the same name has already been added.
+    DuplicateSystem(&'static str),
+    /// The system declared access to a resource more than once.
+    DuplicateAccess {
+        system: &'static str,
+        resource: &'static str,
+    },
+}
+
+impl Display for ScheduleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScheduleError::DuplicateSystem(name) => write!(f, "{} was already added", name),
+            ScheduleError::DuplicateAccess { system, resource } => {
+                write!(f, "{} declared access to {} twice", system, resource)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScheduleError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct Body {
+        position: Vec<f64>,
+    }
+
+    #[derive(Debug, Default)]
+    struct Colony {
+        alloc: Allocator<Self>,
+        food: Component<Self, f64>,
+    }
+
+    dynamic_arena!(Colony);
+
+    impl HasAllocator for Colony {
+        fn allocator(&self) -> &Allocator<Self> {
+            &self.alloc
+        }
+
+        fn allocator_mut(&mut self) -> &mut Allocator<Self> {
+            &mut self.alloc
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct Trade(usize);
+
+    fn world() -> World {
+        let mut colony = Colony::default();
+        for food in [1.0, -1.0, 2.0].iter() {
+            let id = colony.alloc.create();
+            colony.food.insert(id, *food);
+        }
+
+        let mut world = World::default();
+        world.insert(Body::default());
+        world.insert(colony);
+        world.insert(Trade(0));
+        world
+    }
+
+    fn schedule() -> Schedule {
+        let mut schedule = Schedule::default();
+
+        schedule
+            .add_system("update_positions", Access::default().write::<Body>(), |s| {
+                s.get_mut::<Body>().position.push(1.0);
+            })
+            .unwrap();
+
+        schedule
+            .add_system("update_food", Access::default().write::<Colony>(), |s| {
+                let colony = s.get::<Colony>();
+                for (food, id) in colony.alloc.zip_id_and_filter(colony.food.iter()) {
+                    if *food < 0.0 {
+                        s.commands.kill(*id.value);
+                    }
+                }
+            })
+            .unwrap();
+
+        schedule
+            .add_system(
+                "build_trade",
+                Access::default()
+                    .read::<Colony>()
+                    .read::<Body>()
+                    .write::<Trade>(),
+                |s| {
+                    s.get_mut::<Trade>().0 = s.get::<Colony>().alloc.len();
+                },
+            )
+            .unwrap();
+
+        schedule
+    }
+
+    #[test]
+    fn non_conflicting_systems_share_a_batch() {
+        let schedule = schedule();
+
+        let expected = vec![vec!["update_positions", "update_food"], vec!["build_trade"]];
+        assert_eq!(expected, schedule.batches());
+
+        let dependencies = schedule.dependencies("build_trade");
+        assert_eq!(2, dependencies.len());
+        assert_eq!("update_positions", dependencies[0].0);
+    }
+
+    #[test]
+    fn commands_apply_before_next_batch() {
+        let schedule = schedule();
+        let mut world = world();
+
+        schedule.run(&mut world);
+
+        assert_eq!(2, world.get::<Colony>().alloc.len());
+        assert_eq!(2, world.get::<Trade>().0);
+        assert_eq!(vec![1.0], world.get::<Body>().position);
+    }
+
+    #[test]
+    fn sync_point_starts_a_new_batch() {
+        let mut schedule = Schedule::default();
+        let read = || Access::default().read::<Body>();
+
+        assert_eq!(Ok(0), schedule.add_system("a", read(), |_| {}));
+        schedule.add_sync();
+        assert_eq!(Ok(1), schedule.add_system("b", read(), |_| {}));
+        assert_eq!(Ok(1), schedule.add_system("c", read(), |_| {}));
+    }
+
+    #[test]
+    fn invalid_systems_are_rejected() {
+        let mut schedule = schedule();
+
+        let duplicate = schedule.add_system("update_food", Access::default(), |_| {});
+        assert_eq!(
+            Err(ScheduleError::DuplicateSystem("update_food")),
+            duplicate
+        );
+
+        let access = Access::default().read::<Trade>().write::<Trade>();
+        let result = schedule.add_system("trade_report", access, |_| {});
+        assert_eq!(
+            Err(ScheduleError::DuplicateAccess {
+                system: "trade_report",
+                resource: std::any::type_name::<Trade>()
+            }),
+            result
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "did not declare access")]
+    fn undeclared_access_panics() {
+        let mut schedule = Schedule::default();
+        schedule
+            .add_system("sneaky", Access::default().read::<Body>(), |s| {
+                s.get_mut::<Body>();
+            })
+            .unwrap();
+
+        schedule.run(&mut world());
+    }
+}
@@ -0,0 +1,556 @@
+use crate::*;
+use bit_vec::BitVec;
+use std::fmt::{Display, Formatter};
+
+/// A 64-bit FNV-1a hasher that writes integers as little-endian bytes.
+///
+/// Unlike `std::hash::Hasher`, the digest does not depend on the platform or the Rust version, so
+/// it can be compared between machines.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct StateHasher(u64);
+
+impl Default for StateHasher {
+    fn default() -> Self {
+        Self(Self::OFFSET)
+    }
+}
+
+impl StateHasher {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    pub fn write_u8(&mut self, value: u8) {
+        self.write(&[value]);
+    }
+
+    pub fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    pub fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    /// Writes the value as a `u64`, so that the digest is the same on 32-bit platforms.
+    pub fn write_usize(&mut self, value: usize) {
+        self.write_u64(value as u64);
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// A value that can be hashed into a digest that is stable across machines, for detecting when
+/// simulations that should be in lockstep have diverged.
+///
+/// Storage hashes its values in index order. The generations that storage uses to synchronize
+/// with its allocator are not hashed, since they depend on when the storage was last validated
+/// rather than on what it contains.
+pub trait StateHash {
+    fn state_hash(&self, hasher: &mut StateHasher);
+
+    fn state_digest(&self) -> u64 {
+        let mut hasher = StateHasher::new();
+        self.state_hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+macro_rules! state_hash_int {
+    ($( $t:ty => $write:ident as $as:ty, )*) => {
+        $(
+            impl StateHash for $t {
+                fn state_hash(&self, hasher: &mut StateHasher) {
+                    hasher.$write(*self as $as);
+                }
+            }
+        )*
+    };
+}
+
+state_hash_int! {
+    u8 => write_u8 as u8,
+    u16 => write_u32 as u32,
+    u32 => write_u32 as u32,
+    u64 => write_u64 as u64,
+    usize => write_u64 as u64,
+    i8 => write_u8 as u8,
+    i16 => write_u32 as u32,
+    i32 => write_u32 as u32,
+    i64 => write_u64 as u64,
+    isize => write_u64 as u64,
+    bool => write_u8 as u8,
+    char => write_u32 as u32,
+}
+
+impl StateHash for f32 {
+    fn state_hash(&self, hasher: &mut StateHasher) {
+        hasher.write_u32(self.to_bits());
+    }
+}
+
+impl StateHash for f64 {
+    fn state_hash(&self, hasher: &mut StateHasher) {
+        hasher.write_u64(self.to_bits());
+    }
+}
+
+impl StateHash for () {
+    fn state_hash(&self, _hasher: &mut StateHasher) {}
+}
+
+impl StateHash for str {
+    fn state_hash(&self, hasher: &mut StateHasher) {
+        hasher.write_usize(self.len());
+        hasher.write(self.as_bytes());
+    }
+}
+
+impl StateHash for String {
+    fn state_hash(&self, hasher: &mut StateHasher) {
+        self.as_str().state_hash(hasher);
+    }
+}
+
+impl<T: StateHash + ?Sized> StateHash for &T {
+    fn state_hash(&self, hasher: &mut StateHasher) {
+        (**self).state_hash(hasher);
+    }
+}
+
+impl<T: StateHash + ?Sized> StateHash for Box<T> {
+    fn state_hash(&self, hasher: &mut StateHasher) {
+        (**self).state_hash(hasher);
+    }
+}
+
+impl<T: StateHash> StateHash for Option<T> {
+    fn state_hash(&self, hasher: &mut StateHasher) {
+        match self {
+            Some(value) => {
+                hasher.write_u8(1);
+                value.state_hash(hasher);
+            }
+            None => hasher.write_u8(0),
+        }
+    }
+}
+
+impl<T: StateHash> StateHash for [T] {
+    fn state_hash(&self, hasher: &mut StateHasher) {
+        hasher.write_usize(self.len());
+        for value in self {
+            value.state_hash(hasher);
+        }
+    }
+}
+
+impl<T: StateHash, const N: usize> StateHash for [T; N] {
+    fn state_hash(&self, hasher: &mut StateHasher) {
+        self[..].state_hash(hasher);
+    }
+}
+
+impl<T: StateHash> StateHash for Vec<T> {
+    fn state_hash(&self, hasher: &mut StateHasher) {
+        self[..].state_hash(hasher);
+    }
+}
+
+macro_rules! state_hash_tuple {
+    ($( ( $( $t:ident $i:tt ),* ), )*) => {
+        $(
+            impl<$( $t: StateHash ),*> StateHash for ( $( $t, )* ) {
+                fn state_hash(&self, hasher: &mut StateHasher) {
+                    $( self.$i.state_hash(hasher); )*
+                }
+            }
+        )*
+    };
+}
+
+state_hash_tuple! {
+    (A 0),
+    (A 0, B 1),
+    (A 0, B 1, C 2),
+    (A 0, B 1, C 2, D 3),
+}
+
+impl StateHash for BitVec {
+    fn state_hash(&self, hasher: &mut StateHasher) {
+        hasher.write_usize(self.len());
+        for block in self.blocks() {
+            hasher.write_u32(block);
+        }
+    }
+}
+
+/// The digest of each component of an arena, in the order that they were added.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ArenaDigest {
+    name: &'static str,
+    components: Vec<(&'static str, u64)>,
+}
+
+impl ArenaDigest {
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            components: vec![],
+        }
+    }
+
+    pub fn component<T: StateHash + ?Sized>(&mut self, name: &'static str, value: &T) -> &mut Self {
+        self.components.push((name, value.state_digest()));
+        self
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn get(&self, component: &str) -> Option<u64> {
+        self.components
+            .iter()
+            .find(|(name, _)| *name == component)
+            .map(|(_, digest)| *digest)
+    }
+
+    pub fn components(&self) -> impl Iterator<Item = (&'static str, u64)> + '_ {
+        self.components.iter().copied()
+    }
+
+    /// The digest of the whole arena, combining the names and digests of its components.
+    pub fn digest(&self) -> u64 {
+        let mut hasher = StateHasher::new();
+        self.name.state_hash(&mut hasher);
+        self.components.state_hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// An arena that can digest each of its components separately, see [`state_hash!`].
+pub trait ArenaHash: Arena + Sized + 'static {
+    fn hash_components(&self, digest: &mut ArenaDigest);
+
+    fn arena_digest(&self) -> ArenaDigest {
+        let mut digest = ArenaDigest::new(Self::NAME);
+        self.hash_components(&mut digest);
+        digest
+    }
+}
+
+/// Digests of a set of arenas, for comparing the state of two simulations.
+///
+/// Arenas are compared in the order that they were added, so every peer must add them in the same
+/// order.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct StateDigest {
+    arenas: Vec<ArenaDigest>,
+}
+
+impl StateDigest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add<A: ArenaHash>(&mut self, arena: &A) -> &mut Self {
+        self.arenas.push(arena.arena_digest());
+        self
+    }
+
+    /// Adds an arena digest that was built by hand, such as for an arena without `ArenaHash`.
+    pub fn insert(&mut self, arena: ArenaDigest) -> &mut Self {
+        self.arenas.push(arena);
+        self
+    }
+
+    pub fn get(&self, arena: &str) -> Option<&ArenaDigest> {
+        self.arenas.iter().find(|digest| digest.name == arena)
+    }
+
+    pub fn arenas(&self) -> impl Iterator<Item = &ArenaDigest> {
+        self.arenas.iter()
+    }
+
+    /// The digest of every arena, to be exchanged between peers each tick.
+    pub fn combined(&self) -> u64 {
+        let mut hasher = StateHasher::new();
+        hasher.write_usize(self.arenas.len());
+        for arena in &self.arenas {
+            hasher.write_u64(arena.digest());
+        }
+        hasher.finish()
+    }
+
+    /// The first arena and component that differ between the two digests, or `None` if they match.
+    pub fn diff(&self, other: &Self) -> Option<Divergence> {
+        let len = self.arenas.len().max(other.arenas.len());
+
+        for i in 0..len {
+            let (lhs, rhs) = match (self.arenas.get(i), other.arenas.get(i)) {
+                (Some(lhs), Some(rhs)) if lhs.name == rhs.name => (lhs, rhs),
+                (lhs, rhs) => {
+                    let arena = lhs.or(rhs).unwrap().name;
+                    return Some(Divergence::arena(arena));
+                }
+            };
+
+            if lhs.components.len() != rhs.components.len() {
+                return Some(Divergence::arena(lhs.name));
+            }
+
+            let component = lhs
+                .components
+                .iter()
+                .zip(&rhs.components)
+                .find(|(l, r)| l != r);
+
+            if let Some(((name, _), _)) = component {
+                return Some(Divergence {
+                    arena: lhs.name,
+                    component: Some(*name),
+                });
+            }
+        }
+
+        None
+    }
+}
+
+/// Where two [`StateDigest`]s first differ.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Divergence {
+    pub arena: &'static str,
+    /// `None` if the arena, or the components that it hashes, are not the same in both digests.
+    pub component: Option<&'static str>,
+}
+
+impl Divergence {
+    fn arena(arena: &'static str) -> Self {
+        Self {
+            arena,
+            component: None,
+        }
+    }
+}
+
+impl Display for Divergence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.component {
+            Some(component) => write!(f, "{}.{}", self.arena, component),
+            None => write!(f, "{}", self.arena),
+        }
+    }
+}
+
+/// Implements [`StateHash`] and [`ArenaHash`] for an arena struct, hashing the listed fields in
+/// order. The allocator should be listed so that killed and reused ids are detected.
+///
+/// ```ignore
+/// state_hash! {
+///     Colony {
+///         alloc,
+///         name,
+///         population,
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! state_hash {
+    (
+        $arena:ty {
+            $( $field:ident ),* $(,)?
+        }
+    ) => {
+        impl $crate::StateHash for $arena {
+            fn state_hash(&self, hasher: &mut $crate::StateHasher) {
+                $(
+                    $crate::StateHash::state_hash(&self.$field, hasher);
+                )*
+            }
+        }
+
+        impl $crate::ArenaHash for $arena {
+            fn hash_components(&self, digest: &mut $crate::ArenaDigest) {
+                $(
+                    digest.component(stringify!($field), &self.$field);
+                )*
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::allocator::test::GenerationalArena;
+
+    #[derive(Debug, Default)]
+    struct Ship {
+        alloc: Allocator<Self>,
+        name: Component<Self, String>,
+        fuel: Component<Self, f64>,
+        escort: IdMap<Self, Id<Self>>,
+    }
+
+    dynamic_arena!(Ship);
+
+    state_hash! {
+        Ship {
+            alloc,
+            name,
+            fuel,
+            escort,
+        }
+    }
+
+    impl Ship {
+        fn create(&mut self, name: &str, fuel: f64) -> Id<Self> {
+            let id = self.alloc.create();
+            self.name.insert(id, name.to_string());
+            self.fuel.insert(id, fuel);
+            id.value
+        }
+    }
+
+    #[test]
+    fn fnv_1a_reference_values() {
+        assert_eq!(0xcbf2_9ce4_8422_2325, StateHasher::new().finish());
+
+        let mut hasher = StateHasher::new();
+        hasher.write(b"a");
+        assert_eq!(0xaf63_dc4c_8601_ec8c, hasher.finish());
+    }
+
+    #[test]
+    fn digest_is_stable() {
+        let mut hasher = StateHasher::new();
+        ((1u32, -1i64), 0.5f64, "id", Some('x')).state_hash(&mut hasher);
+
+        assert_eq!(hasher.finish(), {
+            let mut hasher = StateHasher::new();
+            hasher.write_u32(1);
+            hasher.write_u64(u64::MAX);
+            hasher.write_u64(0.5f64.to_bits());
+            hasher.write_usize(2);
+            hasher.write(b"id");
+            hasher.write_u8(1);
+            hasher.write_u32('x' as u32);
+            hasher.finish()
+        });
+    }
+
+    #[test]
+    fn equal_arenas_have_equal_digests() {
+        let mut ships = Ship::default();
+        ships.create("Rocinante", 0.5);
+        ships.create("Tachi", 1.0);
+        let mut copy = Ship::default();
+        copy.create("Rocinante", 0.5);
+        copy.create("Tachi", 1.0);
+
+        let mut lhs = StateDigest::new();
+        lhs.add(&ships);
+        let mut rhs = StateDigest::new();
+        rhs.add(&copy);
+
+        assert_eq!(lhs.combined(), rhs.combined());
+        assert_eq!(None, lhs.diff(&rhs));
+    }
+
+    #[test]
+    fn id_map_digest_is_independent_of_insertion_order() {
+        let mut lhs = Ship::default();
+        let mut rhs = Ship::default();
+        let ids = [lhs.create("Rocinante", 0.5), lhs.create("Tachi", 1.0)];
+        rhs.create("Rocinante", 0.5);
+        rhs.create("Tachi", 1.0);
+
+        for (a, b) in [(0, 1), (1, 0)] {
+            let (a, b) = (ids[a], ids[b]);
+            lhs.escort.insert(lhs.alloc.validate(a).unwrap(), b);
+        }
+        for (a, b) in [(1, 0), (0, 1)] {
+            let (a, b) = (ids[a], ids[b]);
+            rhs.escort.insert(rhs.alloc.validate(a).unwrap(), b);
+        }
+
+        assert_eq!(lhs.escort.state_digest(), rhs.escort.state_digest());
+    }
+
+    #[test]
+    fn diff_reports_first_differing_component() {
+        let mut ships = Ship::default();
+        ships.create("Rocinante", 0.5);
+        let mut changed = Ship::default();
+        let id = changed.create("Rocinante", 0.5);
+        *changed.fuel.get_mut(changed.alloc.validate(id).unwrap()) += 0.25;
+
+        let mut lhs = StateDigest::new();
+        lhs.add(&ships);
+        let mut rhs = StateDigest::new();
+        rhs.add(&changed);
+
+        let divergence = lhs.diff(&rhs).unwrap();
+
+        assert_ne!(lhs.combined(), rhs.combined());
+        assert_eq!(Some("fuel"), divergence.component);
+        assert_eq!("Ship.fuel", divergence.to_string());
+    }
+
+    #[test]
+    fn allocator_digest_includes_generations() {
+        let mut lhs = Allocator::<GenerationalArena>::default();
+        let mut rhs = Allocator::<GenerationalArena>::default();
+
+        let id = lhs.create().value;
+        lhs.kill(id);
+        lhs.create();
+        rhs.create();
+
+        assert_eq!(lhs.ids().count(), rhs.ids().count());
+        assert_ne!(lhs.state_digest(), rhs.state_digest());
+    }
+
+    #[test]
+    fn allocator_digest_excludes_counters() {
+        let mut allocator = Allocator::<GenerationalArena>::default();
+        allocator.create();
+        let digest = allocator.state_digest();
+
+        allocator.reserve();
+
+        assert_eq!(digest, allocator.state_digest());
+    }
+
+    #[test]
+    fn diff_reports_missing_arena() {
+        let mut ships = Ship::default();
+        ships.create("Rocinante", 0.5);
+
+        let mut lhs = StateDigest::new();
+        lhs.add(&ships);
+        let rhs = StateDigest::new();
+
+        assert_eq!(
+            Some(Divergence {
+                arena: "Ship",
+                component: None
+            }),
+            lhs.diff(&rhs)
+        );
+    }
+}
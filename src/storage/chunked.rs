@@ -0,0 +1,474 @@
+use crate::*;
+use std::iter::{Flatten, Take};
+use std::marker::PhantomData;
+use std::ops::*;
+
+/// A component that stores its values in lane-aligned chunks of `N` values.
+///
+/// Elementwise arithmetic works on whole `[T; N]` chunks so that the compiler can auto-vectorize
+/// it. Lanes past `len()` in the final chunk are padding and hold `T::default()`.
+#[derive(Debug)]
+pub struct ChunkedComponent<ID, T, const N: usize> {
+    chunks: Vec<[T; N]>,
+    len: usize,
+    marker: PhantomData<ID>,
+}
+
+impl<ID, T, const N: usize> Default for ChunkedComponent<ID, T, N> {
+    fn default() -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::CHECK;
+        Self {
+            chunks: vec![],
+            len: 0,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<ID, T: Clone, const N: usize> Clone for ChunkedComponent<ID, T, N> {
+    fn clone(&self) -> Self {
+        Self {
+            chunks: self.chunks.clone(),
+            len: self.len,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<ID, T, const N: usize> ChunkedComponent<ID, T, N> {
+    /// Fails to build wherever a component is made if `N` is zero:
+    ///
+    /// ```compile_fail,E0080
+    /// use gen_id::*;
+    ///
+    /// #[derive(Debug)]
+    /// struct Planet;
+    /// fixed_arena!(Planet);
+    ///
+    /// let mass = ChunkedComponent::<Planet, f64, 0>::default();
+    /// ```
+    const CHECK: () = assert!(N > 0, "chunks must hold at least one value");
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::CHECK;
+        Self {
+            chunks: Vec::with_capacity(capacity.div_ceil(N)),
+            len: 0,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn iter(&self) -> Iter<'_, ID, T, N> {
+        Iter {
+            iter: self.chunks.iter().flatten().take(self.len),
+            marker: PhantomData,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, ID, T, N> {
+        IterMut {
+            iter: self.chunks.iter_mut().flatten().take(self.len),
+            marker: PhantomData,
+        }
+    }
+
+    /// The underlying chunks, including the padding lanes of the final chunk.
+    pub fn chunks(&self) -> &[[T; N]] {
+        &self.chunks
+    }
+
+    /// The underlying chunks, including the padding lanes of the final chunk.
+    pub fn chunks_mut(&mut self) -> &mut [[T; N]] {
+        &mut self.chunks
+    }
+
+    pub fn fill_with<F: FnMut() -> T>(&mut self, mut f: F) {
+        self.iter_mut().into_iter().for_each(|v| *v = f());
+    }
+}
+
+impl<ID, T: Copy + Default, const N: usize> ChunkedComponent<ID, T, N> {
+    pub fn fill(&mut self, value: T) {
+        self.for_each_lane(|v| *v = value);
+    }
+
+    /// Applies `f` to every value, one chunk at a time. Padding lanes are not visited.
+    pub fn for_each_lane<F: FnMut(&mut T)>(&mut self, mut f: F) {
+        let full = self.len / N;
+        let tail = self.len % N;
+
+        for chunk in &mut self.chunks[..full] {
+            for value in chunk.iter_mut() {
+                f(value);
+            }
+        }
+
+        if tail != 0 {
+            for value in &mut self.chunks[full][..tail] {
+                f(value);
+            }
+        }
+    }
+
+    /// Applies `f` to every pair of values, one chunk at a time. Padding lanes are not visited.
+    ///
+    /// # Panics
+    /// Panics if the two components have different lengths.
+    pub fn zip_lanes<U: Copy, F: FnMut(&mut T, U)>(
+        &mut self,
+        rhs: &ChunkedComponent<ID, U, N>,
+        mut f: F,
+    ) {
+        assert_eq!(
+            self.len,
+            rhs.len,
+            "Length mismatch: {:?}",
+            std::any::type_name::<Self>()
+        );
+
+        let full = self.len / N;
+        let tail = self.len % N;
+
+        for (a, b) in self.chunks[..full].iter_mut().zip(&rhs.chunks[..full]) {
+            for i in 0..N {
+                f(&mut a[i], b[i]);
+            }
+        }
+
+        if tail != 0 {
+            let a = &mut self.chunks[full];
+            let b = &rhs.chunks[full];
+            for i in 0..tail {
+                f(&mut a[i], b[i]);
+            }
+        }
+    }
+}
+
+impl<ID: Arena, T, const N: usize> ChunkedComponent<ID, T, N> {
+    pub fn get<I: ValidId<ID>>(&self, id: I) -> &T {
+        let index = self.checked_index(id);
+        &self.chunks[index / N][index % N]
+    }
+
+    pub fn get_mut<I: ValidId<ID>>(&mut self, id: I) -> &mut T {
+        let index = self.checked_index(id);
+        &mut self.chunks[index / N][index % N]
+    }
+
+    fn checked_index<I: ValidId<ID>>(&self, id: I) -> usize {
+        let index = id.index();
+
+        assert!(
+            index < self.len,
+            "Invalid index: {:?}",
+            std::any::type_name::<Self>()
+        );
+
+        index
+    }
+}
+
+impl<ID: Arena, T: Copy + Default, const N: usize> ChunkedComponent<ID, T, N> {
+    pub fn insert<I: ValidId<ID>>(&mut self, id: I, value: T) {
+        let index = id.index();
+
+        if index < self.len {
+            self.chunks[index / N][index % N] = value;
+        } else if index == self.len {
+            if index % N == 0 {
+                self.chunks.push([T::default(); N]);
+            }
+            self.chunks[index / N][index % N] = value;
+            self.len += 1;
+        } else {
+            panic!("Invalid index: {:?}", std::any::type_name::<Self>());
+        }
+    }
+}
+
+impl<ID, T, const N: usize> MemoryUsage for ChunkedComponent<ID, T, N> {
+    fn memory_usage(&self) -> usize {
+        self.chunks.capacity() * std::mem::size_of::<[T; N]>()
+    }
+}
+
+/// Hashes the same as a `Component` with the same values, so the digest does not depend on how
+/// the values are stored.
+impl<ID, T: StateHash, const N: usize> StateHash for ChunkedComponent<ID, T, N> {
+    fn state_hash(&self, hasher: &mut StateHasher) {
+        hasher.write_usize(self.len);
+        for value in self.iter() {
+            value.state_hash(hasher);
+        }
+    }
+}
+
+macro_rules! chunked_op {
+    ($op:ident, $op_fn:ident, $assign:ident, $assign_fn:ident) => {
+        impl<ID, T, const N: usize> $assign<&ChunkedComponent<ID, T, N>>
+            for ChunkedComponent<ID, T, N>
+        where
+            T: $assign + Copy + Default,
+        {
+            fn $assign_fn(&mut self, rhs: &ChunkedComponent<ID, T, N>) {
+                self.zip_lanes(rhs, |a, b| a.$assign_fn(b));
+            }
+        }
+
+        impl<ID, T, const N: usize> $assign<T> for ChunkedComponent<ID, T, N>
+        where
+            T: $assign + Copy + Default,
+        {
+            fn $assign_fn(&mut self, rhs: T) {
+                self.for_each_lane(|a| a.$assign_fn(rhs));
+            }
+        }
+
+        impl<ID, T, const N: usize> $op<&ChunkedComponent<ID, T, N>> for &ChunkedComponent<ID, T, N>
+        where
+            T: $op<Output = T> + Copy + Default,
+        {
+            type Output = ChunkedComponent<ID, T, N>;
+
+            fn $op_fn(self, rhs: &ChunkedComponent<ID, T, N>) -> Self::Output {
+                let mut output = self.clone();
+                output.zip_lanes(rhs, |a, b| *a = a.$op_fn(b));
+                output
+            }
+        }
+
+        impl<ID, T, const N: usize> $op<T> for &ChunkedComponent<ID, T, N>
+        where
+            T: $op<Output = T> + Copy + Default,
+        {
+            type Output = ChunkedComponent<ID, T, N>;
+
+            fn $op_fn(self, rhs: T) -> Self::Output {
+                let mut output = self.clone();
+                output.for_each_lane(|a| *a = a.$op_fn(rhs));
+                output
+            }
+        }
+    };
+}
+
+chunked_op!(Add, add, AddAssign, add_assign);
+chunked_op!(Sub, sub, SubAssign, sub_assign);
+chunked_op!(Mul, mul, MulAssign, mul_assign);
+chunked_op!(Div, div, DivAssign, div_assign);
+
+type Values<'a, T, const N: usize> = Take<Flatten<std::slice::Iter<'a, [T; N]>>>;
+type ValuesMut<'a, T, const N: usize> = Take<Flatten<std::slice::IterMut<'a, [T; N]>>>;
+
+pub struct Iter<'a, ID, T, const N: usize> {
+    iter: Values<'a, T, N>,
+    marker: PhantomData<ID>,
+}
+
+impl<'a, ID, T, const N: usize> IntoIterator for Iter<'a, ID, T, N> {
+    type Item = &'a T;
+    type IntoIter = Values<'a, T, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter
+    }
+}
+
+impl<'a, ID, T, const N: usize> ContextualIterator for Iter<'a, ID, T, N> {
+    type Context = ID;
+}
+
+pub struct IterMut<'a, ID, T, const N: usize> {
+    iter: ValuesMut<'a, T, N>,
+    marker: PhantomData<ID>,
+}
+
+impl<'a, ID, T, const N: usize> IntoIterator for IterMut<'a, ID, T, N> {
+    type Item = &'a mut T;
+    type IntoIter = ValuesMut<'a, T, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter
+    }
+}
+
+impl<'a, ID, T, const N: usize> ContextualIterator for IterMut<'a, ID, T, N> {
+    type Context = ID;
+}
+
+impl<'a, ID, T, const N: usize> IntoIterator for &'a ChunkedComponent<ID, T, N> {
+    type Item = &'a T;
+    type IntoIter = Values<'a, T, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter().into_iter()
+    }
+}
+
+impl<'a, ID, T, const N: usize> IntoIterator for &'a mut ChunkedComponent<ID, T, N> {
+    type Item = &'a mut T;
+    type IntoIter = ValuesMut<'a, T, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut().into_iter()
+    }
+}
+
+impl<ID, T, const N: usize> ContextualIterator for &ChunkedComponent<ID, T, N> {
+    type Context = ID;
+}
+
+impl<ID, T, const N: usize> ContextualIterator for &mut ChunkedComponent<ID, T, N> {
+    type Context = ID;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::allocator::test::FixedArena;
+
+    #[test]
+    fn insert_across_chunk_boundary() {
+        let mut component = ChunkedComponent::<FixedArena, u32, 4>::default();
+        for (i, value) in [0, 1, 2, 3, 4].iter().enumerate() {
+            component.insert(Id::first(i), *value);
+        }
+
+        assert_eq!(5, component.len());
+        assert_eq!(2, component.chunks().len());
+        assert_eq!(&4, component.get(Id::first(4)));
+    }
+
+    #[test]
+    fn insert_at_index_less_than_len_replaces_existing_value() {
+        let mut component = ChunkedComponent::<FixedArena, u32, 4>::default();
+        for (i, value) in [0, 1, 2].iter().enumerate() {
+            component.insert(Id::first(i), *value);
+        }
+
+        component.insert(Id::first(1), 5);
+
+        assert_eq!(3, component.len());
+        assert_eq!(&5, component.get(Id::first(1)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn insert_given_invalid_id_panics() {
+        let mut component = ChunkedComponent::<FixedArena, u32, 4>::default();
+        component.insert(Id::first(0), 0);
+
+        component.insert(Id::first(2), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn get_padding_lane_panics() {
+        let mut component = ChunkedComponent::<FixedArena, u32, 4>::default();
+        component.insert(Id::first(0), 0);
+
+        component.get(Id::first(1));
+    }
+
+    #[test]
+    fn iter_skips_padding() {
+        let mut component = ChunkedComponent::<FixedArena, u32, 4>::default();
+        for (i, value) in [1, 2, 3, 4, 5].iter().enumerate() {
+            component.insert(Id::first(i), *value);
+        }
+
+        let values: Vec<u32> = component.iter().into_iter().copied().collect();
+
+        assert_eq!(vec![1, 2, 3, 4, 5], values);
+    }
+
+    #[test]
+    fn elementwise_ops() {
+        let mut a = ChunkedComponent::<FixedArena, u32, 4>::default();
+        for (i, value) in [2, 4, 6, 8, 10].iter().enumerate() {
+            a.insert(Id::first(i), *value);
+        }
+        let mut b = ChunkedComponent::<FixedArena, u32, 4>::default();
+        for (i, value) in [1, 2, 3, 4, 5].iter().enumerate() {
+            b.insert(Id::first(i), *value);
+        }
+
+        let values = |c: &ChunkedComponent<FixedArena, u32, 4>| -> Vec<u32> {
+            c.iter().into_iter().copied().collect()
+        };
+
+        assert_eq!(vec![3, 6, 9, 12, 15], values(&(&a + &b)));
+        assert_eq!(vec![1, 2, 3, 4, 5], values(&(&a - &b)));
+        assert_eq!(vec![2, 8, 18, 32, 50], values(&(&a * &b)));
+        assert_eq!(vec![2, 2, 2, 2, 2], values(&(&a / &b)));
+        assert_eq!(vec![1, 2, 3, 4, 5], values(&(&a / 2)));
+    }
+
+    #[test]
+    fn division_does_not_touch_padding() {
+        let mut a = ChunkedComponent::<FixedArena, u32, 4>::default();
+        for (i, value) in [2, 4, 6].iter().enumerate() {
+            a.insert(Id::first(i), *value);
+        }
+        let mut b = ChunkedComponent::<FixedArena, u32, 4>::default();
+        for (i, value) in [1, 2, 3].iter().enumerate() {
+            b.insert(Id::first(i), *value);
+        }
+
+        a /= &b;
+
+        assert_eq!([2, 2, 2, 0], a.chunks()[0]);
+    }
+
+    #[test]
+    fn assign_ops_with_scalar() {
+        let mut a = ChunkedComponent::<FixedArena, u32, 4>::default();
+        for (i, value) in [1, 2, 3, 4, 5].iter().enumerate() {
+            a.insert(Id::first(i), *value);
+        }
+
+        a += 1;
+        a *= 2;
+
+        assert_eq!(&12, a.get(Id::first(4)));
+        assert_eq!([0; 3], a.chunks()[1][1..]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn length_mismatch_panics() {
+        let mut a = ChunkedComponent::<FixedArena, u32, 4>::default();
+        for (i, value) in [1, 2, 3].iter().enumerate() {
+            a.insert(Id::first(i), *value);
+        }
+        let mut b = ChunkedComponent::<FixedArena, u32, 4>::default();
+        for (i, value) in [1, 2].iter().enumerate() {
+            b.insert(Id::first(i), *value);
+        }
+
+        a += &b;
+    }
+
+    #[test]
+    fn state_hash_matches_component() {
+        let mut chunked = ChunkedComponent::<FixedArena, u32, 4>::default();
+        let mut component = Component::<FixedArena, u32>::default();
+        for (i, value) in [1, 2, 3, 4, 5].iter().enumerate() {
+            chunked.insert(Id::first(i), *value);
+            component.insert(Id::first(i), *value);
+        }
+
+        assert_eq!(component.state_digest(), chunked.state_digest());
+    }
+}
@@ -0,0 +1,166 @@
+use super::*;
+
+/// Copies values from a parent arena down to the child entities that link to it.
+impl<ID, T: Clone> Component<ID, T> {
+    /// Sets each value to the value of the parent it links to, or to `fallback` if the link is
+    /// missing or dead.
+    ///
+    /// # Panics
+    /// Panics if `links` and `self` have different lengths.
+    pub fn gather_from<P: Arena, L>(
+        &mut self,
+        source: &Component<P, T>,
+        links: &Component<ID, L>,
+        alloc: &Allocator<P>,
+        fallback: T,
+    ) where
+        P::Allocator: Validate<P>,
+        L: LinkId<P> + Copy,
+    {
+        self.assert_same_len(links);
+
+        for (value, link) in self.values.iter_mut().zip(links.values.iter()) {
+            *value = gather(&source.values, *link, &**alloc, &fallback);
+        }
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<ID, T: Clone + Send + Sync> Component<ID, T> {
+    /// A parallel version of [`gather_from`](Component::gather_from).
+    pub fn par_gather_from<P: Arena, L>(
+        &mut self,
+        source: &Component<P, T>,
+        links: &Component<ID, L>,
+        alloc: &Allocator<P>,
+        fallback: T,
+    ) where
+        P::Allocator: Validate<P> + Sync,
+        L: LinkId<P> + Copy + Send + Sync,
+    {
+        self.assert_same_len(links);
+
+        let source = &source.values;
+        let alloc = &**alloc;
+        let fallback = &fallback;
+
+        self.values
+            .par_iter_mut()
+            .zip(links.values.par_iter())
+            .for_each(|(value, link)| {
+                *value = gather(source, *link, alloc, fallback);
+            });
+    }
+}
+
+fn gather<P: Arena, T: Clone, L: LinkId<P>>(
+    source: &[T],
+    link: L,
+    alloc: &impl Validate<P>,
+    fallback: &T,
+) -> T {
+    link.link_id()
+        .and_then(|id| alloc.validate(id))
+        .and_then(|id| source.get(id.index()))
+        .unwrap_or(fallback)
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::allocator::test::{FixedArena, GenerationalArena};
+
+    struct Child;
+    dynamic_arena!(Child);
+
+    #[test]
+    fn gather_from_fixed_parent() {
+        let mut parents = Allocator::<FixedArena>::default();
+        let mut children = Allocator::<Child>::default();
+        let mut temperature = Component::<FixedArena, f64>::default();
+        let mut links = Component::<Child, Id<FixedArena>>::default();
+        let mut gathered = Component::<Child, f64>::default();
+
+        let a = parents.create();
+        let b = parents.create();
+        temperature.insert(a, 10.0);
+        temperature.insert(b, 20.0);
+
+        for parent in [b, a, b].iter() {
+            let child = children.create();
+            links.insert(child, *parent);
+            gathered.insert(child, 0.0);
+        }
+
+        gathered.gather_from(&temperature, &links, &parents, -1.0);
+
+        assert_eq!(vec![20.0, 10.0, 20.0], gathered.values);
+    }
+
+    #[test]
+    fn gather_from_id_link_uses_fallback() {
+        let mut parents = Allocator::<GenerationalArena>::default();
+        let mut children = Allocator::<Child>::default();
+        let mut temperature = Component::<GenerationalArena, f64>::default();
+        let mut links = IdLink::<Child, GenerationalArena>::default();
+        let mut gathered = Component::<Child, f64>::default();
+
+        let a = parents.create().value;
+        let b = parents.create().value;
+        temperature.insert(parents.validate(a).unwrap(), 10.0);
+        temperature.insert(parents.validate(b).unwrap(), 20.0);
+
+        for parent in [Some(a), None, Some(b)].iter() {
+            let child = children.create();
+            links.insert(child, parent.and_then(|p| parents.validate(p)));
+            gathered.insert(child, 0.0);
+        }
+
+        parents.kill(b);
+
+        gathered.gather_from(&temperature, &links, &parents, -1.0);
+
+        assert_eq!(vec![10.0, -1.0, -1.0], gathered.values);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_gather_from_matches_gather_from() {
+        let mut parents = Allocator::<FixedArena>::default();
+        let mut children = Allocator::<Child>::default();
+        let mut temperature = Component::<FixedArena, f64>::default();
+        let mut links = Component::<Child, Id<FixedArena>>::default();
+        let mut gathered = Component::<Child, f64>::default();
+        let mut par_gathered = Component::<Child, f64>::default();
+
+        let parent_ids: Vec<_> = (0..10).map(|_| parents.create()).collect();
+        for (i, parent) in parent_ids.iter().enumerate() {
+            temperature.insert(parent, i as f64);
+        }
+
+        for i in 0..100 {
+            let child = children.create();
+            links.insert(child, parent_ids[i * 7 % 10]);
+            gathered.insert(child, 0.0);
+            par_gathered.insert(child, 0.0);
+        }
+
+        gathered.gather_from(&temperature, &links, &parents, -1.0);
+        par_gathered.par_gather_from(&temperature, &links, &parents, -1.0);
+
+        assert_eq!(gathered.values, par_gathered.values);
+    }
+
+    #[test]
+    #[should_panic]
+    fn gather_from_length_mismatch_panics() {
+        let parents = Allocator::<FixedArena>::default();
+        let temperature = Component::<FixedArena, f64>::default();
+        let links = Component::<Child, Id<FixedArena>>::default();
+        let mut gathered = Component::<Child, f64>::default();
+        gathered.values.push(0.0);
+
+        gathered.gather_from(&temperature, &links, &parents, -1.0);
+    }
+}
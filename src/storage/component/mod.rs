@@ -0,0 +1,485 @@
+use crate::*;
+use std::marker::PhantomData;
+use std::ops::*;
+
+mod gather;
+mod reduce;
+
+// #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug)]
+pub struct Component<ID, T> {
+    values: Vec<T>,
+    marker: PhantomData<ID>,
+}
+
+impl<ID, T> Default for Component<ID, T> {
+    fn default() -> Self {
+        Self {
+            values: vec![],
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<ID, T> Component<ID, T> {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            values: Vec::with_capacity(capacity),
+            marker: PhantomData,
+        }
+    }
+
+    /// The iterator carries `ID` as its context, so it zips with iterators over the same arena:
+    ///
+    /// ```
+    /// use gen_id::*;
+    /// use iter_context::ContextualIterator;
+    ///
+    /// #[derive(Debug)]
+    /// struct Planet;
+    /// fixed_arena!(Planet);
+    ///
+    /// let planet_mass = Component::<Planet, f64>::default();
+    /// let planet_radius = Component::<Planet, f64>::default();
+    ///
+    /// planet_mass.iter().zip(planet_radius.iter());
+    /// ```
+    ///
+    /// but not with iterators over another arena:
+    ///
+    /// ```compile_fail,E0271
+    /// use gen_id::*;
+    /// use iter_context::ContextualIterator;
+    ///
+    /// #[derive(Debug)]
+    /// struct Planet;
+    /// fixed_arena!(Planet);
+    ///
+    /// #[derive(Debug)]
+    /// struct Moon;
+    /// fixed_arena!(Moon);
+    ///
+    /// let planet_mass = Component::<Planet, f64>::default();
+    /// let moon_mass = Component::<Moon, f64>::default();
+    ///
+    /// planet_mass.iter().zip(moon_mass.iter());
+    /// ```
+    pub fn iter(&self) -> Iter<ID, T> {
+        Iter::new(self.values.iter())
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<ID, T> {
+        IterMut::new(self.values.iter_mut())
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn fill_with<F: FnMut() -> T>(&mut self, mut f: F) {
+        self.iter_mut().for_each(|v| *v = f());
+    }
+}
+
+impl<ID, B: Arena> Relation<B> for Component<ID, Id<B>> {
+    type Owner = ID;
+
+    fn linked(&self, id: Id<B>) -> Vec<usize> {
+        let linked = self.values.iter().enumerate();
+        linked.filter(|(_, v)| **v == id).map(|(i, _)| i).collect()
+    }
+
+    fn relink(&mut self, from: Id<B>, to: Id<B>) {
+        for value in self.values.iter_mut().filter(|v| **v == from) {
+            *value = to;
+        }
+    }
+}
+
+impl<ID, B: Arena> Relation<B> for Component<ID, Option<Id<B>>> {
+    type Owner = ID;
+
+    fn linked(&self, id: Id<B>) -> Vec<usize> {
+        let linked = self.values.iter().enumerate();
+        linked
+            .filter(|(_, v)| **v == Some(id))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    fn relink(&mut self, from: Id<B>, to: Id<B>) {
+        self.replace_links(from, Some(to));
+    }
+}
+
+impl<ID, B: Arena> NullableRelation<B> for Component<ID, Option<Id<B>>> {
+    fn unlink(&mut self, id: Id<B>) {
+        self.replace_links(id, None);
+    }
+}
+
+impl<ID: Arena, T: Clone + Remap> CloneLinks<ID> for Component<ID, T> {
+    type Link = T;
+
+    fn link<I: ValidId<ID>>(&self, id: I) -> T {
+        self.get(id).clone()
+    }
+
+    fn set_link<I: ValidId<ID>>(&mut self, id: I, link: T) {
+        self.insert(id, link);
+    }
+
+    fn remap_link<I: ValidId<ID>>(&mut self, id: I, cx: &CloneContext) {
+        self.get_mut(id).remap(cx);
+    }
+}
+
+impl<ID, B: Arena> Component<ID, Option<Id<B>>> {
+    fn replace_links(&mut self, from: Id<B>, to: Option<Id<B>>) {
+        for value in self.values.iter_mut().filter(|v| **v == Some(from)) {
+            *value = to;
+        }
+    }
+}
+
+impl<ID: Arena, T> Component<ID, T> {
+    pub fn get<I: ValidId<ID>>(&self, id: I) -> &T {
+        assert!(
+            id.index() < self.values.len(),
+            format!("Invalid index: {:?}", std::any::type_name::<Self>())
+        );
+
+        self.values.get(id.index()).unwrap()
+    }
+
+    pub fn get_mut<I: ValidId<ID>>(&mut self, id: I) -> &mut T {
+        assert!(
+            id.index() < self.values.len(),
+            format!("Invalid index: {:?}", std::any::type_name::<Self>())
+        );
+
+        self.values.get_mut(id.index()).unwrap()
+    }
+
+    pub fn insert<I: ValidId<ID>>(&mut self, id: I, value: T) {
+        if let Some(component) = self.values.get_mut(id.index()) {
+            *component = value;
+        } else if self.len() == id.index() {
+            self.values.push(value);
+        } else {
+            panic!("Invalid index: {:?}", std::any::type_name::<Self>());
+        }
+    }
+}
+
+impl<ID, T> Component<ID, T> {
+    /// Creates a new component by applying `f` to each pair of values.
+    ///
+    /// # Panics
+    /// Panics if the two components have different lengths.
+    pub fn zip_map<U, V, F: FnMut(&T, &U) -> V>(
+        &self,
+        rhs: &Component<ID, U>,
+        mut f: F,
+    ) -> Component<ID, V> {
+        self.assert_same_len(rhs);
+
+        let values = self
+            .values
+            .iter()
+            .zip(rhs.values.iter())
+            .map(|(a, b)| f(a, b))
+            .collect();
+
+        Component {
+            values,
+            marker: PhantomData,
+        }
+    }
+
+    /// Overwrites `out` with `f` applied to each pair of values, reusing its allocation.
+    ///
+    /// # Panics
+    /// Panics if the two components have different lengths.
+    pub fn zip_map_into<U, V, F: FnMut(&T, &U) -> V>(
+        &self,
+        rhs: &Component<ID, U>,
+        out: &mut Component<ID, V>,
+        mut f: F,
+    ) {
+        self.assert_same_len(rhs);
+
+        out.values.clear();
+        out.values.extend(
+            self.values
+                .iter()
+                .zip(rhs.values.iter())
+                .map(|(a, b)| f(a, b)),
+        );
+    }
+
+    fn assert_same_len<U>(&self, rhs: &Component<ID, U>) {
+        assert_eq!(
+            self.len(),
+            rhs.len(),
+            "Length mismatch: {:?}",
+            std::any::type_name::<Self>()
+        );
+    }
+}
+
+macro_rules! component_op {
+    ($op:ident, $op_fn:ident, $assign:ident, $assign_fn:ident) => {
+        impl<ID, T: $assign + Copy> $assign<&Component<ID, T>> for Component<ID, T> {
+            fn $assign_fn(&mut self, rhs: &Component<ID, T>) {
+                self.assert_same_len(rhs);
+
+                self.values
+                    .iter_mut()
+                    .zip(rhs.values.iter())
+                    .for_each(|(a, b)| a.$assign_fn(*b));
+            }
+        }
+
+        impl<ID, T: $assign + Copy> $assign<T> for Component<ID, T> {
+            fn $assign_fn(&mut self, rhs: T) {
+                self.values.iter_mut().for_each(|a| a.$assign_fn(rhs));
+            }
+        }
+
+        impl<ID, T: $op<Output = T> + Copy> $op<&Component<ID, T>> for &Component<ID, T> {
+            type Output = Component<ID, T>;
+
+            fn $op_fn(self, rhs: &Component<ID, T>) -> Self::Output {
+                self.zip_map(rhs, |a, b| a.$op_fn(*b))
+            }
+        }
+
+        impl<ID, T: $op<Output = T> + Copy> $op<T> for &Component<ID, T> {
+            type Output = Component<ID, T>;
+
+            fn $op_fn(self, rhs: T) -> Self::Output {
+                Component {
+                    values: self.values.iter().map(|a| a.$op_fn(rhs)).collect(),
+                    marker: PhantomData,
+                }
+            }
+        }
+    };
+}
+
+component_op!(Add, add, AddAssign, add_assign);
+component_op!(Sub, sub, SubAssign, sub_assign);
+component_op!(Mul, mul, MulAssign, mul_assign);
+component_op!(Div, div, DivAssign, div_assign);
+
+impl<ID, T: Clone> Component<ID, T> {
+    pub fn fill(&mut self, value: T) {
+        self.iter_mut().for_each(|v| *v = value.clone());
+    }
+}
+
+impl<ID, T> MemoryUsage for Component<ID, T> {
+    fn memory_usage(&self) -> usize {
+        self.values.capacity() * std::mem::size_of::<T>()
+    }
+}
+
+impl<ID, T: StateHash> StateHash for Component<ID, T> {
+    fn state_hash(&self, hasher: &mut StateHasher) {
+        self.values.state_hash(hasher);
+    }
+}
+
+impl<'a, ID, T> IntoIterator for &'a Component<ID, T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.iter()
+    }
+}
+
+impl<'a, ID, T> IntoIterator for &'a mut Component<ID, T> {
+    type Item = &'a mut T;
+    type IntoIter = std::slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.iter_mut()
+    }
+}
+
+impl<'a, ID, T> ContextualIterator for &'a Component<ID, T> {
+    type Context = ID;
+}
+
+impl<'a, ID, T> ContextualIterator for &'a mut Component<ID, T> {
+    type Context = ID;
+}
+
+#[cfg(feature = "rayon")]
+impl<ID, T: Send + Sync> Component<ID, T> {
+    pub fn par_iter(&self) -> rayon::slice::Iter<T> {
+        self.values.par_iter()
+    }
+
+    pub fn par_iter_mut(&mut self) -> rayon::slice::IterMut<T> {
+        self.values.par_iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::allocator::test::FixedArena;
+
+    #[test]
+    fn get_at_valid_index() {
+        let mut components = Component::<FixedArena, u32>::default();
+        let id = Id::first(0);
+
+        components.insert(id, 5);
+
+        assert_eq!(&5, components.get(id));
+    }
+
+    #[test]
+    fn insert_at_index_equals_len_extends_vec() {
+        let mut components = Component::<FixedArena, u32>::default();
+        let id = Id::first(0);
+
+        components.insert(id, 5);
+
+        assert_eq!(1, components.len());
+        assert_eq!(5, components.values[0]);
+    }
+
+    #[test]
+    fn insert_at_index_less_than_len_replaces_existing_value() {
+        let mut components = Component::<FixedArena, u32>::default();
+        let id = Id::first(0);
+
+        components.insert(id, 3);
+        components.insert(id, 5);
+
+        assert_eq!(1, components.len());
+        assert_eq!(5, components.values[0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn get_given_invalid_id_panics() {
+        let id = Id::first(0);
+        let component = Component::<FixedArena, u32>::default();
+
+        component.get(id);
+    }
+
+    #[test]
+    #[should_panic]
+    fn get_mut_given_invalid_id_panics() {
+        let id = Id::first(0);
+        let mut component = Component::<FixedArena, u32>::default();
+
+        component.get_mut(id);
+    }
+
+    #[test]
+    #[should_panic]
+    fn insert_given_invalid_id_panics() {
+        let id = Id::first(1);
+        let mut component = Component::<FixedArena, u32>::default();
+
+        component.insert(id, 0);
+    }
+
+    #[test]
+    fn elementwise_ops() {
+        let a = Component::<FixedArena, u32> {
+            values: vec![2, 4, 6],
+            marker: PhantomData,
+        };
+        let b = Component::<FixedArena, u32> {
+            values: vec![1, 2, 3],
+            marker: PhantomData,
+        };
+
+        assert_eq!(vec![3, 6, 9], (&a + &b).values);
+        assert_eq!(vec![1, 2, 3], (&a - &b).values);
+        assert_eq!(vec![2, 8, 18], (&a * &b).values);
+        assert_eq!(vec![2, 2, 2], (&a / &b).values);
+        assert_eq!(vec![1, 2, 3], (&a / 2).values);
+    }
+
+    #[test]
+    fn assign_ops() {
+        let mut a = Component::<FixedArena, u32> {
+            values: vec![1, 2, 3],
+            marker: PhantomData,
+        };
+        let b = Component::<FixedArena, u32> {
+            values: vec![1, 1, 1],
+            marker: PhantomData,
+        };
+
+        a += &b;
+        a *= 3;
+
+        assert_eq!(vec![6, 9, 12], a.values);
+    }
+
+    #[test]
+    fn zip_map_creates_new_component() {
+        let a = Component::<FixedArena, u32> {
+            values: vec![1, 2, 3],
+            marker: PhantomData,
+        };
+        let b = Component::<FixedArena, u32> {
+            values: vec![3, 2, 1],
+            marker: PhantomData,
+        };
+
+        let c = a.zip_map(&b, |a, b| a > b);
+
+        assert_eq!(vec![false, false, true], c.values);
+    }
+
+    #[test]
+    fn zip_map_into_overwrites_output() {
+        let a = Component::<FixedArena, u32> {
+            values: vec![1, 2, 3],
+            marker: PhantomData,
+        };
+        let b = Component::<FixedArena, u32> {
+            values: vec![3, 2, 1],
+            marker: PhantomData,
+        };
+        let mut c = Component::<FixedArena, u32> {
+            values: vec![0; 5],
+            marker: PhantomData,
+        };
+
+        a.zip_map_into(&b, &mut c, |a, b| a * b);
+
+        assert_eq!(vec![3, 4, 3], c.values);
+    }
+
+    #[test]
+    #[should_panic]
+    fn length_mismatch_panics() {
+        let mut a = Component::<FixedArena, u32> {
+            values: vec![1, 2, 3],
+            marker: PhantomData,
+        };
+        let b = Component::<FixedArena, u32> {
+            values: vec![1, 2],
+            marker: PhantomData,
+        };
+
+        a += &b;
+    }
+}
@@ -0,0 +1,493 @@
+use super::*;
+
+/// Reductions from a child arena into a parent arena.
+///
+/// `values` and `links` are zipped contextual iterators over the child arena (or table), where
+/// each link points at the parent entity that the value is aggregated into. Values whose link is
+/// missing or dead are skipped.
+impl<ID1: Arena, T> Component<ID1, T>
+where
+    ID1::Allocator: Validate<ID1>,
+{
+    /// Resets every value to `init`, then folds each linked value into its parent using `f`.
+    pub fn fold_from<V, L, F>(
+        &mut self,
+        init: T,
+        values: V,
+        links: L,
+        alloc: &Allocator<ID1>,
+        mut f: F,
+    ) where
+        T: Clone,
+        V: ContextualIterator + IntoIterator,
+        L: ContextualIterator<Context = V::Context> + IntoIterator,
+        L::Item: LinkId<ID1>,
+        F: FnMut(&mut T, V::Item),
+    {
+        self.fill(init);
+
+        for (value, link) in values.zip(links) {
+            if let Some(id) = link.link_id().and_then(|id| alloc.validate(id)) {
+                f(self.get_mut(id), value);
+            }
+        }
+    }
+
+    pub fn sum_from<'v, V, L>(&mut self, values: V, links: L, alloc: &Allocator<ID1>)
+    where
+        T: AddAssign + Copy + Default + 'v,
+        V: ContextualIterator + IntoIterator<Item = &'v T>,
+        L: ContextualIterator<Context = V::Context> + IntoIterator,
+        L::Item: LinkId<ID1>,
+    {
+        self.fold_from(T::default(), values, links, alloc, |sum, value| {
+            *sum += *value
+        });
+    }
+
+    /// Sums a component of the child arena into the parent each child is linked to.
+    pub fn sum_from_link<ID2: Arena>(
+        &mut self,
+        component: &Component<ID2, T>,
+        link: &IdLink<ID2, ID1>,
+        alloc: &Allocator<ID1>,
+    ) where
+        T: AddAssign + Copy + Default,
+    {
+        self.sum_from(component.iter(), link.iter(), alloc);
+    }
+}
+
+impl<ID1: Arena> Component<ID1, usize>
+where
+    ID1::Allocator: Validate<ID1>,
+{
+    /// Counts the number of living links to each parent.
+    pub fn count_from<L>(&mut self, links: L, alloc: &Allocator<ID1>)
+    where
+        L: IntoIterator,
+        L::Item: LinkId<ID1>,
+    {
+        self.fill(0);
+
+        for link in links {
+            if let Some(id) = link.link_id().and_then(|id| alloc.validate(id)) {
+                *self.get_mut(id) += 1;
+            }
+        }
+    }
+}
+
+/// Reductions that are undefined for parents without children, which are set to `None`.
+impl<ID1: Arena, T> Component<ID1, Option<T>>
+where
+    ID1::Allocator: Validate<ID1>,
+{
+    pub fn min_from<'v, V, L>(&mut self, values: V, links: L, alloc: &Allocator<ID1>)
+    where
+        T: PartialOrd + Copy + 'v,
+        V: ContextualIterator + IntoIterator<Item = &'v T>,
+        L: ContextualIterator<Context = V::Context> + IntoIterator,
+        L::Item: LinkId<ID1>,
+    {
+        self.fold_from(None, values, links, alloc, |min, value| match min {
+            Some(min) if *min <= *value => {}
+            _ => *min = Some(*value),
+        });
+    }
+
+    pub fn max_from<'v, V, L>(&mut self, values: V, links: L, alloc: &Allocator<ID1>)
+    where
+        T: PartialOrd + Copy + 'v,
+        V: ContextualIterator + IntoIterator<Item = &'v T>,
+        L: ContextualIterator<Context = V::Context> + IntoIterator,
+        L::Item: LinkId<ID1>,
+    {
+        self.fold_from(None, values, links, alloc, |max, value| match max {
+            Some(max) if *max >= *value => {}
+            _ => *max = Some(*value),
+        });
+    }
+}
+
+impl<ID1: Arena> Component<ID1, Option<f64>>
+where
+    ID1::Allocator: Validate<ID1>,
+{
+    pub fn mean_from<'v, V, L>(&mut self, values: V, links: L, alloc: &Allocator<ID1>)
+    where
+        V: ContextualIterator + IntoIterator<Item = &'v f64>,
+        L: ContextualIterator<Context = V::Context> + IntoIterator,
+        L::Item: LinkId<ID1>,
+    {
+        self.fill(None);
+
+        let mut counts = vec![0u32; self.len()];
+
+        for (value, link) in values.zip(links) {
+            if let Some(id) = link.link_id().and_then(|id| alloc.validate(id)) {
+                let count = &mut counts[id.index()];
+                *count += 1;
+
+                let mean = self.get_mut(id).get_or_insert(0.0);
+                *mean += (*value - *mean) / f64::from(*count);
+            }
+        }
+    }
+}
+
+/// Reductions from sparse storage.
+///
+/// `IdMap` and `Graph` iterate in index order, so values are folded in the same order regardless
+/// of how the map or graph was built, and floating-point results are reproducible.
+impl<ID1: Arena, T> Component<ID1, T>
+where
+    ID1::Allocator: Validate<ID1>,
+{
+    /// Resets every value to `init`, then folds each map value into the parent of its key.
+    ///
+    /// Entries whose key is dead in `children`, or whose link is missing or dead, are skipped.
+    pub fn fold_from_map<ID2, V, L, F>(
+        &mut self,
+        init: T,
+        values: &IdMap<ID2, V>,
+        links: &Component<ID2, L>,
+        children: &Allocator<ID2>,
+        alloc: &Allocator<ID1>,
+        mut f: F,
+    ) where
+        T: Clone,
+        ID2: Arena,
+        ID2::Allocator: Validate<ID2>,
+        L: LinkId<ID1> + Copy,
+        F: FnMut(&mut T, &V),
+    {
+        self.fill(init);
+
+        for (child, value) in values.iter() {
+            let parent = children
+                .validate(*child)
+                .and_then(|child| links.get(child).link_id())
+                .and_then(|id| alloc.validate(id));
+
+            if let Some(id) = parent {
+                f(self.get_mut(id), value);
+            }
+        }
+    }
+
+    pub fn sum_from_map<ID2, L>(
+        &mut self,
+        values: &IdMap<ID2, T>,
+        links: &Component<ID2, L>,
+        children: &Allocator<ID2>,
+        alloc: &Allocator<ID1>,
+    ) where
+        T: AddAssign + Copy + Default,
+        ID2: Arena,
+        ID2::Allocator: Validate<ID2>,
+        L: LinkId<ID1> + Copy,
+    {
+        self.fold_from_map(
+            T::default(),
+            values,
+            links,
+            children,
+            alloc,
+            |sum, value| *sum += *value,
+        );
+    }
+
+    /// Resets every value to `init`, then folds the weight of each edge into the node it leaves.
+    ///
+    /// Edges with a dead endpoint are skipped.
+    pub fn fold_edges_from<W, F>(
+        &mut self,
+        init: T,
+        graph: &Graph<ID1, W>,
+        alloc: &Allocator<ID1>,
+        mut f: F,
+    ) where
+        T: Clone,
+        F: FnMut(&mut T, &W),
+    {
+        self.fill(init);
+
+        for (edge, weight) in graph.edges() {
+            if alloc.validate(edge.to).is_none() {
+                continue;
+            }
+
+            if let Some(id) = alloc.validate(edge.from) {
+                f(self.get_mut(id), weight);
+            }
+        }
+    }
+
+    pub fn sum_edges_from(&mut self, graph: &Graph<ID1, T>, alloc: &Allocator<ID1>)
+    where
+        T: AddAssign + Copy + Default,
+    {
+        self.fold_edges_from(T::default(), graph, alloc, |sum, weight| *sum += *weight);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::allocator::test::{FixedArena, GenerationalArena};
+
+    struct Child;
+    dynamic_arena!(Child);
+
+    struct Table;
+
+    #[test]
+    fn sum_from_fixed_parent() {
+        let mut parents = Allocator::<FixedArena>::default();
+        let mut children = Allocator::<Child>::default();
+        let mut values = Component::<Child, f64>::default();
+        let mut links = Component::<Child, Id<FixedArena>>::default();
+
+        let parent = parents.create();
+        parents.create();
+        for value in &[1.0, 2.0, 6.0] {
+            let child = children.create();
+            values.insert(child, *value);
+            links.insert(child, parent);
+        }
+
+        let mut sum = Component::<FixedArena, f64> {
+            values: vec![0.0; 2],
+            marker: PhantomData,
+        };
+        sum.sum_from(&values, &links, &parents);
+
+        assert_eq!(vec![9.0, 0.0], sum.values);
+    }
+
+    #[test]
+    fn sum_from_link_with_fixed_parent() {
+        let mut parents = Allocator::<FixedArena>::default();
+        let mut children = Allocator::<Child>::default();
+        let mut values = Component::<Child, f64>::default();
+        let mut link = IdLink::<Child, FixedArena>::default();
+
+        let parent = parents.create();
+        for value in &[1.0, 2.0] {
+            let child = children.create();
+            values.insert(child, *value);
+            link.insert(child, Some(parent));
+        }
+        let orphan = children.create();
+        values.insert(orphan, 4.0);
+        link.insert(orphan, None::<Id<FixedArena>>);
+
+        let mut sum = Component::<FixedArena, f64>::default();
+        sum.insert(parent, 0.0);
+        sum.sum_from_link(&values, &link, &parents);
+
+        assert_eq!(vec![3.0], sum.values);
+    }
+
+    #[test]
+    fn sum_from_skips_dead_parents() {
+        let mut parents = Allocator::<GenerationalArena>::default();
+        let mut children = Allocator::<Child>::default();
+        let mut values = Component::<Child, f64>::default();
+        let mut links = Component::<Child, Id<GenerationalArena>>::default();
+
+        let a = parents.create().value;
+        let b = parents.create().value;
+        for (value, parent) in &[(1.0, a), (2.0, a), (6.0, a), (4.0, b)] {
+            let child = children.create();
+            values.insert(child, *value);
+            links.insert(child, *parent);
+        }
+        parents.kill(a);
+
+        let mut sum = Component::<GenerationalArena, f64> {
+            values: vec![0.0; 2],
+            marker: PhantomData,
+        };
+        sum.sum_from(&values, &links, &parents);
+
+        assert_eq!(vec![0.0, 4.0], sum.values);
+    }
+
+    #[test]
+    fn count_from_counts_living_links() {
+        let mut parents = Allocator::<GenerationalArena>::default();
+        let mut children = Allocator::<Child>::default();
+        let mut links = Component::<Child, Id<GenerationalArena>>::default();
+
+        let a = parents.create().value;
+        let b = parents.create().value;
+        for parent in &[a, a, a, b] {
+            links.insert(children.create(), *parent);
+        }
+        parents.kill(b);
+
+        let mut count = Component::<GenerationalArena, usize> {
+            values: vec![0; 2],
+            marker: PhantomData,
+        };
+        count.count_from(&links, &parents);
+
+        assert_eq!(vec![3, 0], count.values);
+    }
+
+    #[test]
+    fn min_max_and_mean_from() {
+        let mut parents = Allocator::<FixedArena>::default();
+        let mut children = Allocator::<Child>::default();
+        let mut values = Component::<Child, f64>::default();
+        let mut links = Component::<Child, Id<FixedArena>>::default();
+
+        let parent = parents.create();
+        parents.create();
+        for value in &[1.0, 2.0, 6.0] {
+            let child = children.create();
+            values.insert(child, *value);
+            links.insert(child, parent);
+        }
+
+        let mut min = Component::<FixedArena, Option<f64>> {
+            values: vec![None; 2],
+            marker: PhantomData,
+        };
+        let mut max = Component::<FixedArena, Option<f64>> {
+            values: vec![None; 2],
+            marker: PhantomData,
+        };
+        let mut mean = Component::<FixedArena, Option<f64>> {
+            values: vec![None; 2],
+            marker: PhantomData,
+        };
+        min.min_from(&values, &links, &parents);
+        max.max_from(&values, &links, &parents);
+        mean.mean_from(&values, &links, &parents);
+
+        assert_eq!(vec![Some(1.0), None], min.values);
+        assert_eq!(vec![Some(6.0), None], max.values);
+        assert_eq!(vec![Some(3.0), None], mean.values);
+    }
+
+    #[test]
+    fn fold_from_id_link() {
+        let mut parents = Allocator::<GenerationalArena>::default();
+        let mut children = Allocator::<Child>::default();
+        let mut values = Component::<Child, f64>::default();
+        let mut link = IdLink::<Child, GenerationalArena>::default();
+
+        let parent_ids = [parents.create().value, parents.create().value];
+        for (i, value) in [1.0, 2.0, 6.0].iter().enumerate() {
+            let child = children.create();
+            values.insert(child, *value);
+            link.insert(child, parents.validate(parent_ids[i % 2]));
+        }
+
+        let mut product = Component::<GenerationalArena, f64> {
+            values: vec![0.0; 2],
+            marker: PhantomData,
+        };
+        product.fold_from(1.0, &values, link.iter(), &parents, |p, v| *p *= v);
+
+        assert_eq!(vec![6.0, 2.0], product.values);
+    }
+
+    #[test]
+    fn sum_from_id_column() {
+        let mut parents = Allocator::<FixedArena>::default();
+        parents.create();
+        let parent = parents.create();
+
+        let mut values = Column::<Table, f64>::default();
+        let mut links = IdColumn::<Table, FixedArena>::default();
+        values.push(3.0);
+        links.push(Some(parent));
+        values.push(5.0);
+        links.push(None::<Id<FixedArena>>);
+
+        let mut sum = Component::<FixedArena, f64> {
+            values: vec![0.0; 2],
+            marker: PhantomData,
+        };
+        sum.sum_from(values.iter(), links.iter(), &parents);
+
+        assert_eq!(vec![0.0, 3.0], sum.values);
+    }
+
+    #[test]
+    fn sum_from_map_is_independent_of_insertion_order() {
+        let mut parents = Allocator::<GenerationalArena>::default();
+        let mut children = Allocator::<Child>::default();
+        let mut links = Component::<Child, Id<GenerationalArena>>::default();
+
+        let parent = parents.create().value;
+        parents.create();
+        let ids = (0..3)
+            .map(|_| {
+                let child = children.create();
+                links.insert(child, parent);
+                child.value
+            })
+            .collect::<Vec<_>>();
+        let values = [0.1, 0.2, 0.3];
+
+        let mut forward = IdMap::<Child, f64>::default();
+        for (id, value) in ids.iter().zip(&values) {
+            forward.insert(children.validate(*id).unwrap(), *value);
+        }
+
+        let mut reverse = IdMap::<Child, f64>::default();
+        for (id, value) in ids.iter().zip(&values).rev() {
+            reverse.insert(children.validate(*id).unwrap(), *value);
+        }
+
+        let mut a = Component::<GenerationalArena, f64> {
+            values: vec![0.0; 2],
+            marker: PhantomData,
+        };
+        let mut b = Component::<GenerationalArena, f64> {
+            values: vec![0.0; 2],
+            marker: PhantomData,
+        };
+        a.sum_from_map(&forward, &links, &children, &parents);
+        b.sum_from_map(&reverse, &links, &children, &parents);
+
+        assert_eq!(a.values[0].to_bits(), b.values[0].to_bits());
+        assert_eq!(a.values[0].to_bits(), (0.1f64 + 0.2 + 0.3).to_bits());
+
+        children.kill(ids[2]);
+        a.sum_from_map(&forward, &links, &children, &parents);
+
+        assert_eq!(vec![0.1 + 0.2, 0.0], a.values);
+    }
+
+    #[test]
+    fn sum_edges_from_skips_dead_nodes() {
+        let mut alloc = Allocator::<GenerationalArena>::default();
+        let ids = (0..3).map(|_| alloc.create().value).collect::<Vec<_>>();
+
+        let mut graph = Graph::<GenerationalArena, f64>::default();
+        {
+            let valid = |i: usize| alloc.validate(ids[i]).unwrap();
+            graph.insert_ids(valid(0), valid(1), 1.0);
+            graph.insert_ids(valid(0), valid(2), 2.0);
+            graph.insert_ids(valid(1), valid(0), 4.0);
+        }
+
+        let mut sum = Component::<GenerationalArena, f64> {
+            values: vec![0.0; 3],
+            marker: PhantomData,
+        };
+
+        sum.sum_edges_from(&graph, &alloc);
+        assert_eq!(vec![3.0, 4.0, 0.0], sum.values);
+
+        alloc.kill(ids[2]);
+        sum.sum_edges_from(&graph, &alloc);
+        assert_eq!(vec![1.0, 4.0, 0.0], sum.values);
+    }
+}
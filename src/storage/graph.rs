@@ -1,14 +1,17 @@
 use crate::*;
-use fnv::FnvHashMap as HashMap;
-use std::collections::hash_map::Entry;
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, HashSet};
 
+/// Weighted edges between entities of the same arena.
+///
+/// Edges are kept sorted by `from` and then `to`, so iteration order is deterministic.
 #[derive(Debug, Default)]
-pub struct Graph<A, W> {
-    edges: HashMap<Edge<A>, W>,
+pub struct Graph<A: Arena, W> {
+    edges: BTreeMap<Edge<A>, W>,
     generation: AllocGen<A>,
 }
 
-impl<A, W: PartialOrd> Graph<A, W> {
+impl<A: Arena, W: PartialOrd> Graph<A, W> {
     pub fn insert_min<E: ValidEdge<A>>(&mut self, edge: E, weight: W) {
         match self.edges.entry(edge.edge()) {
             Entry::Occupied(mut o) => {
@@ -36,7 +39,7 @@ impl<A, W: PartialOrd> Graph<A, W> {
     }
 }
 
-impl<A, W> Graph<A, W> {
+impl<A: Arena, W> Graph<A, W> {
     pub fn insert<E: ValidEdge<A>>(&mut self, edge: E, weight: W) {
         self.edges.insert(edge.edge(), weight);
     }
@@ -70,23 +73,82 @@ impl<A, W> Graph<A, W> {
         self.edges.clear();
     }
 
+    /// Every edge in the graph, including those with dead endpoints, ordered by `from` and then `to`.
+    pub fn edges(&self) -> impl Iterator<Item = (&Edge<A>, &W)> + '_ {
+        self.edges.iter()
+    }
+
+    /// The edges from `node`, ordered by `to`.
     pub fn get_edges_from<I: ValidId<A>>(
         &self,
         node: I,
     ) -> impl Iterator<Item = (&Edge<A>, &W)> + '_ {
         let node = node.id();
-        self.edges.iter().filter(move |&(e, _)| e.from == node)
+        // the first id of index 0 has the lowest bits, so it sorts before every other `to`
+        self.edges
+            .range(Edge::new(node, Id::first(0))..)
+            .take_while(move |&(e, _)| e.from == node)
+    }
+}
+
+impl<A: Arena + 'static, W: Clone> CloneRows<A> for Graph<A, W> {
+    fn clone_rows(&mut self, clones: &[(Id<A>, Id<A>)], cx: &CloneContext) {
+        let sources = clones.iter().map(|(src, _)| *src).collect::<HashSet<_>>();
+
+        let edges = self
+            .edges
+            .iter()
+            .filter(|(e, _)| sources.contains(&e.from) || sources.contains(&e.to))
+            .map(|(e, w)| (Edge::new(cx.remap(e.from), cx.remap(e.to)), w.clone()))
+            .collect::<Vec<_>>();
+
+        self.edges.extend(edges);
+    }
+
+    fn clone_rows_into(&self, dest: &mut Self, clones: &[(Id<A>, Id<A>)], cx: &CloneContext) {
+        let sources = clones.iter().map(|(src, _)| *src).collect::<HashSet<_>>();
+
+        let edges = self
+            .edges
+            .iter()
+            .filter(|(e, _)| sources.contains(&e.from) || sources.contains(&e.to))
+            .filter_map(|(e, w)| {
+                let edge = Edge::new(cx.get(e.from)?, cx.get(e.to)?);
+                Some((edge, w.clone()))
+            });
+
+        dest.edges.extend(edges);
+    }
+}
+
+impl<A: Arena, W> MemoryUsage for Graph<A, W> {
+    fn memory_usage(&self) -> usize {
+        btree_map_usage(&self.edges)
+    }
+}
+
+impl<A: Arena, W: StateHash> StateHash for Graph<A, W> {
+    fn state_hash(&self, hasher: &mut StateHasher) {
+        hasher.write_usize(self.edges.len());
+        for (edge, weight) in &self.edges {
+            edge.state_hash(hasher);
+            weight.state_hash(hasher);
+        }
     }
 }
 
 impl<ARENA: Arena<Allocator = DynamicAllocator<ARENA>>, W> Graph<ARENA, W> {
     pub fn kill(&mut self, allocator: &Allocator<ARENA>) {
         if let Some(killed) = allocator.last_killed() {
-            self.edges.retain(|edge, _| !edge.contains(killed));
-            self.generation.increment();
+            self.kill_id(killed);
         }
     }
 
+    fn kill_id(&mut self, id: Id<ARENA>) {
+        self.edges.retain(|edge, _| !edge.contains(id));
+        self.generation.increment();
+    }
+
     pub fn validate<'a>(&'a mut self, allocator: &'a Allocator<ARENA>) -> Valid<&'a Self> {
         self.synchronize(allocator);
 
@@ -114,13 +176,19 @@ impl<ARENA: Arena<Allocator = DynamicAllocator<ARENA>>, W> Graph<ARENA, W> {
     }
 }
 
-impl<'a, A, W> Valid<'_, &'a Graph<A, W>> {
+impl<ARENA: Arena<Allocator = DynamicAllocator<ARENA>>, W> OnKill<ARENA> for Graph<ARENA, W> {
+    fn on_kill(&mut self, id: Id<ARENA>) {
+        self.kill_id(id);
+    }
+}
+
+impl<'a, A: Arena, W> Valid<'_, &'a Graph<A, W>> {
     pub fn iter(&'a self) -> impl Iterator<Item = (Valid<'a, &'a Edge<A>>, &W)> {
         self.value.edges.iter().map(|(e, w)| (Valid::new(e), w))
     }
 }
 
-impl<'a, A, W> Valid<'_, &'a mut Graph<A, W>> {
+impl<'a, A: Arena, W> Valid<'_, &'a mut Graph<A, W>> {
     pub fn iter_mut(&'a mut self) -> impl Iterator<Item = (Valid<'a, &'a Edge<A>>, &mut W)> {
         self.value.edges.iter_mut().map(|(e, w)| (Valid::new(e), w))
     }
@@ -222,4 +290,52 @@ mod tests {
 
         assert_eq!(Some(&2), graph.get(&edge));
     }
+
+    #[test]
+    fn edges_are_ordered_by_from_then_to() {
+        let mut alloc = Allocator::<FixedArena>::default();
+        let mut graph = Graph::<FixedArena, u32>::default();
+
+        let a = alloc.create();
+        let b = alloc.create();
+        let c = alloc.create();
+
+        graph.insert_ids(b, a, 3);
+        graph.insert_ids(a, c, 2);
+        graph.insert_ids(a, b, 1);
+        graph.insert_ids(c, a, 4);
+
+        let weights = graph.edges().map(|(_, w)| *w).collect::<Vec<_>>();
+        assert_eq!(vec![1, 2, 3, 4], weights);
+
+        let from_a = graph
+            .get_edges_from(a)
+            .map(|(e, _)| e.to)
+            .collect::<Vec<_>>();
+        assert_eq!(vec![b, c], from_a);
+
+        let from_b = graph
+            .get_edges_from(b)
+            .map(|(e, _)| e.to)
+            .collect::<Vec<_>>();
+        assert_eq!(vec![a], from_b);
+    }
+
+    #[test]
+    fn valid_iter_is_ordered() {
+        let mut alloc = Allocator::<GenerationalArena>::default();
+        let mut graph = Graph::<GenerationalArena, u32>::default();
+
+        let a = alloc.create().value;
+        let b = alloc.create().value;
+
+        let valid = |id| alloc.validate(id).unwrap();
+        graph.insert_ids(valid(b), valid(a), 2);
+        graph.insert_ids(valid(a), valid(b), 1);
+
+        let graph = graph.validate(&alloc);
+        let weights = graph.iter().map(|(_, w)| *w).collect::<Vec<_>>();
+
+        assert_eq!(vec![1, 2], weights);
+    }
 }
@@ -2,12 +2,12 @@ use crate::*;
 use std::marker::PhantomData;
 
 #[derive(Debug)]
-pub struct IdLink<A, B> {
+pub struct IdLink<A, B: Arena> {
     component: Component<A, Option<Id<B>>>,
     generation: AllocGen<B>,
 }
 
-impl<A, B> Default for IdLink<A, B> {
+impl<A, B: Arena> Default for IdLink<A, B> {
     fn default() -> Self {
         Self {
             component: Default::default(),
@@ -16,7 +16,7 @@ impl<A, B> Default for IdLink<A, B> {
     }
 }
 
-impl<A, B> std::ops::Deref for IdLink<A, B> {
+impl<A, B: Arena> std::ops::Deref for IdLink<A, B> {
     type Target = Component<A, Option<Id<B>>>;
 
     fn deref(&self) -> &Self::Target {
@@ -24,7 +24,7 @@ impl<A, B> std::ops::Deref for IdLink<A, B> {
     }
 }
 
-impl<A, B> IdLink<A, B> {
+impl<A: Arena, B: Arena> IdLink<A, B> {
     pub fn insert<IA: ValidId<A>, IB: ValidId<B>>(&mut self, id: IA, link: Option<IB>) {
         self.component.insert(id, link.map(|link| link.id()));
     }
@@ -34,6 +34,34 @@ impl<A, B> IdLink<A, B> {
     }
 }
 
+impl<A: Arena, B: Arena + 'static> CloneLinks<A> for IdLink<A, B> {
+    type Link = Option<Id<B>>;
+
+    fn link<I: ValidId<A>>(&self, id: I) -> Option<Id<B>> {
+        self.component.link(id)
+    }
+
+    fn set_link<I: ValidId<A>>(&mut self, id: I, link: Option<Id<B>>) {
+        self.component.set_link(id, link);
+    }
+
+    fn remap_link<I: ValidId<A>>(&mut self, id: I, cx: &CloneContext) {
+        self.component.remap_link(id, cx);
+    }
+}
+
+impl<A, B: Arena> MemoryUsage for IdLink<A, B> {
+    fn memory_usage(&self) -> usize {
+        self.component.memory_usage()
+    }
+}
+
+impl<A, B: Arena> StateHash for IdLink<A, B> {
+    fn state_hash(&self, hasher: &mut StateHasher) {
+        self.component.state_hash(hasher);
+    }
+}
+
 impl<A, B: Arena<Allocator = DynamicAllocator<B>>> IdLink<A, B> {
     pub fn kill(&mut self, id: Id<B>) {
         for link in self.component.iter_mut() {
@@ -94,7 +122,33 @@ impl<A, B: Arena<Allocator = DynamicAllocator<B>>> IdLink<A, B> {
     }
 }
 
-impl<'a, A, B> Valid<'a, &IdLink<A, B>> {
+impl<A, B: Arena<Allocator = DynamicAllocator<B>>> OnKill<B> for IdLink<A, B> {
+    fn on_kill(&mut self, id: Id<B>) {
+        self.kill(id);
+    }
+}
+
+impl<A, B: Arena> Relation<B> for IdLink<A, B> {
+    type Owner = A;
+
+    fn linked(&self, id: Id<B>) -> Vec<usize> {
+        self.component.linked(id)
+    }
+
+    fn relink(&mut self, from: Id<B>, to: Id<B>) {
+        self.component.relink(from, to);
+    }
+}
+
+/// Unlinking leaves the link's generation alone, since `id` need not have been killed. The next
+/// `validate` catches up with the allocator.
+impl<A, B: Arena> NullableRelation<B> for IdLink<A, B> {
+    fn unlink(&mut self, id: Id<B>) {
+        self.component.unlink(id);
+    }
+}
+
+impl<'a, A: Arena, B: Arena> Valid<'a, &IdLink<A, B>> {
     pub fn get<I: ValidId<A>>(&'a self, id: I) -> Option<Valid<'a, Id<B>>> {
         self.value.component.get(id).map(Valid::new)
     }
@@ -107,12 +161,12 @@ impl<'a, A, B> Valid<'a, &IdLink<A, B>> {
     }
 }
 
-pub struct Iter<'a, A, B> {
+pub struct Iter<'a, A, B: Arena> {
     iter: std::slice::Iter<'a, Option<Id<B>>>,
     marker: PhantomData<A>,
 }
 
-impl<'a, A, B> Iterator for Iter<'a, A, B> {
+impl<'a, A, B: Arena> Iterator for Iter<'a, A, B> {
     type Item = Option<Valid<'a, &'a Id<B>>>;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -120,7 +174,7 @@ impl<'a, A, B> Iterator for Iter<'a, A, B> {
     }
 }
 
-impl<'a, A, B> IntoIterator for &'a Valid<'a, &IdLink<A, B>> {
+impl<'a, A: Arena, B: Arena> IntoIterator for &'a Valid<'a, &IdLink<A, B>> {
     type Item = Option<Valid<'a, &'a Id<B>>>;
     type IntoIter = Iter<'a, A, B>;
 
@@ -129,6 +183,54 @@ impl<'a, A, B> IntoIterator for &'a Valid<'a, &IdLink<A, B>> {
     }
 }
 
-impl<'a, A, B> ContextualIterator for &'a Valid<'a, &'a IdLink<A, B>> {
+impl<'a, A: Arena, B: Arena> ContextualIterator for &'a Valid<'a, &'a IdLink<A, B>> {
     type Context = A;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::allocator::test::GenerationalArena;
+
+    struct Owner;
+
+    fixed_arena!(Owner);
+
+    #[test]
+    fn unlink_after_kill_is_resynchronized_by_validate() {
+        let mut owners = Allocator::<Owner>::default();
+        let mut alloc = Allocator::<GenerationalArena>::default();
+        let mut link = IdLink::<Owner, GenerationalArena>::default();
+
+        let target = alloc.create().value;
+        link.insert(owners.create(), alloc.validate(target));
+        link.validate(&alloc);
+
+        alloc.kill(target);
+        link.unlink(target);
+        link.validate(&alloc);
+
+        assert!(link.is_synchronized(&alloc));
+        assert!(link.linked(target).is_empty());
+    }
+
+    #[test]
+    fn unlink_without_kill_keeps_generation() {
+        let mut owners = Allocator::<Owner>::default();
+        let mut alloc = Allocator::<GenerationalArena>::default();
+        let mut link = IdLink::<Owner, GenerationalArena>::default();
+
+        let target = alloc.create().value;
+        link.insert(owners.create(), alloc.validate(target));
+        let other = alloc.create().value;
+        alloc.kill(other);
+        link.validate(&alloc);
+
+        link.unlink(target);
+        link.unlink(target);
+        link.validate(&alloc);
+
+        assert!(link.is_synchronized(&alloc));
+        assert!(link.linked(target).is_empty());
+    }
+}
@@ -1,13 +1,18 @@
 use crate::*;
+use std::collections::BTreeMap;
 
+/// A sparse map from ids to values.
+///
+/// Entries are kept sorted by id, so iteration is in index order and does not depend on the order
+/// that the entries were inserted in.
 // #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug)]
-pub struct IdMap<ID, T> {
-    map: HashMap<Id<ID>, T>,
+pub struct IdMap<ID: Arena, T> {
+    map: BTreeMap<Id<ID>, T>,
     generation: AllocGen<ID>,
 }
 
-impl<ID, T: Clone> Clone for IdMap<ID, T> {
+impl<ID: Arena, T: Clone> Clone for IdMap<ID, T> {
     fn clone(&self) -> Self {
         Self {
             map: self.map.clone(),
@@ -16,24 +21,22 @@ impl<ID, T: Clone> Clone for IdMap<ID, T> {
     }
 }
 
-impl<ID, T> Default for IdMap<ID, T> {
+impl<ID: Arena, T> Default for IdMap<ID, T> {
     fn default() -> Self {
         Self {
-            map: HashMap::default(),
+            map: BTreeMap::default(),
             generation: AllocGen::default(),
         }
     }
 }
 
-impl<ID, T> IdMap<ID, T> {
-    pub fn with_capacity(capacity: usize) -> Self {
-        Self {
-            map: HashMap::with_capacity_and_hasher(capacity, Default::default()),
-            generation: AllocGen::default(),
-        }
+impl<ID: Arena, T> IdMap<ID, T> {
+    #[deprecated(note = "the entries are kept in a `BTreeMap`, use `IdMap::default`")]
+    pub fn with_capacity(_capacity: usize) -> Self {
+        Self::default()
     }
 
-    /// Inserts a valid Id and Value into the hashmap. Does not reset the IdMap's generation value.
+    /// Inserts a valid Id and Value into the map. Does not reset the IdMap's generation value.
     pub fn insert<I: ValidId<ID>>(&mut self, id: I, value: T) {
         self.map.insert(id.id(), value);
     }
@@ -90,6 +93,47 @@ impl<ID, T> IdMap<ID, T> {
     }
 }
 
+impl<ID: Arena, T> OnKill<ID> for IdMap<ID, T> {
+    fn on_kill(&mut self, id: Id<ID>) {
+        self.kill(id);
+    }
+}
+
+impl<ID: Arena + 'static, T: Clone> CloneRows<ID> for IdMap<ID, T> {
+    fn clone_rows(&mut self, clones: &[(Id<ID>, Id<ID>)], _cx: &CloneContext) {
+        for (src, clone) in clones {
+            if let Some(value) = self.map.get(src).cloned() {
+                self.map.insert(*clone, value);
+            }
+        }
+    }
+
+    fn clone_rows_into(&self, dest: &mut Self, clones: &[(Id<ID>, Id<ID>)], _cx: &CloneContext) {
+        for (src, clone) in clones {
+            if let Some(value) = self.map.get(src) {
+                dest.map.insert(*clone, value.clone());
+            }
+        }
+    }
+}
+
+impl<ID: Arena, T> MemoryUsage for IdMap<ID, T> {
+    fn memory_usage(&self) -> usize {
+        btree_map_usage(&self.map)
+    }
+}
+
+/// The digest depends only on the entries, not on the order that they were inserted in.
+impl<ID: Arena, T: StateHash> StateHash for IdMap<ID, T> {
+    fn state_hash(&self, hasher: &mut StateHasher) {
+        hasher.write_usize(self.map.len());
+        for (id, value) in &self.map {
+            id.state_hash(hasher);
+            value.state_hash(hasher);
+        }
+    }
+}
+
 impl<ID: Arena<Allocator = DynamicAllocator<ID>>, T> IdMap<ID, T> {
     pub fn validate<'a>(&'a mut self, allocator: &'a Allocator<ID>) -> Valid<&'a Self> {
         self.synchronize(allocator);
@@ -118,7 +162,7 @@ impl<ID: Arena<Allocator = DynamicAllocator<ID>>, T> IdMap<ID, T> {
     }
 }
 
-impl<'a, ID, T> Valid<'a, &'a IdMap<ID, T>> {
+impl<'a, ID: Arena, T> Valid<'a, &'a IdMap<ID, T>> {
     pub fn iter(&'a self) -> impl Iterator<Item = (Valid<'a, &Id<ID>>, &T)> {
         self.value
             .map
@@ -131,7 +175,7 @@ impl<'a, ID, T> Valid<'a, &'a IdMap<ID, T>> {
     }
 }
 
-impl<'a, ID, T> Valid<'a, &mut IdMap<ID, T>> {
+impl<'a, ID: Arena, T> Valid<'a, &mut IdMap<ID, T>> {
     pub fn iter_mut(&mut self) -> impl Iterator<Item = (Valid<'_, &Id<ID>>, &mut T)> {
         self.value
             .map
@@ -179,4 +223,43 @@ mod tests {
         assert_eq!(None, map.get(b));
         assert_eq!(Some(&2), map.get(c));
     }
+
+    #[test]
+    fn iter_is_in_index_order() {
+        let alloc = &mut Allocator::<Test>::default();
+        let ids = (0..4).map(|_| alloc.create().value).collect::<Vec<_>>();
+
+        let mut forward = IdMap::<Test, usize>::default();
+        let mut reverse = IdMap::<Test, usize>::default();
+        for (i, id) in ids.iter().enumerate() {
+            forward.insert(alloc.validate(*id).unwrap(), i);
+        }
+        for (i, id) in ids.iter().enumerate().rev() {
+            reverse.insert(alloc.validate(*id).unwrap(), i);
+        }
+
+        assert_eq!(
+            vec![0, 1, 2, 3],
+            forward.values().copied().collect::<Vec<_>>()
+        );
+        assert!(forward.iter().eq(reverse.iter()));
+    }
+
+    #[test]
+    fn iter_after_reuse_is_in_index_order() {
+        let alloc = &mut Allocator::<Test>::default();
+        let a = alloc.create().value;
+        let b = alloc.create().value;
+        alloc.kill(a);
+        let c = alloc.create().value;
+
+        let mut map = IdMap::<Test, &str>::default();
+        map.insert(alloc.validate(b).unwrap(), "b");
+        map.insert(alloc.validate(c).unwrap(), "c");
+
+        let map = map.validate(alloc);
+        let keys = map.iter().map(|(id, _)| id.index()).collect::<Vec<_>>();
+
+        assert_eq!(vec![0, 1], keys);
+    }
 }
@@ -61,6 +61,18 @@ impl<C, T> Column<C, T> {
     }
 }
 
+impl<C, T> MemoryUsage for Column<C, T> {
+    fn memory_usage(&self) -> usize {
+        self.values.capacity() * std::mem::size_of::<T>()
+    }
+}
+
+impl<C, T: StateHash> StateHash for Column<C, T> {
+    fn state_hash(&self, hasher: &mut StateHasher) {
+        self.values.state_hash(hasher);
+    }
+}
+
 impl<'a, C, ID> Valid<'a, &Column<C, Id<ID>>>
 where
     ID: Arena<Allocator = DynamicAllocator<ID>>,
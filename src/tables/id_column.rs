@@ -2,12 +2,12 @@ use crate::GenerationCmp;
 use crate::*;
 
 #[derive(Debug)]
-pub struct IdColumn<C, ID> {
+pub struct IdColumn<C, ID: Arena> {
     ids: Column<C, Option<Id<ID>>>,
     generation: AllocGen<ID>,
 }
 
-impl<C, ID> Default for IdColumn<C, ID> {
+impl<C, ID: Arena> Default for IdColumn<C, ID> {
     fn default() -> Self {
         Self {
             ids: Column::default(),
@@ -16,7 +16,7 @@ impl<C, ID> Default for IdColumn<C, ID> {
     }
 }
 
-impl<C, ID> IdColumn<C, ID> {
+impl<C, ID: Arena> IdColumn<C, ID> {
     pub fn with_capacity(capacity: usize) -> Self {
         Self {
             ids: Column::with_capacity(capacity),
@@ -61,6 +61,77 @@ impl<C, ID> IdColumn<C, ID> {
     }
 }
 
+impl<C, ID: Arena + 'static> IdColumn<C, ID> {
+    /// Pushes a copy of the link at `index`, pointed at its clone if the context remaps links.
+    /// Used by `table!` to clone whole rows.
+    pub fn clone_row(&mut self, index: &Index<C>, cx: &CloneContext) -> Index<C> {
+        let link = self.cloned_link(index, cx);
+        self.ids.push(link)
+    }
+
+    /// Pushes a copy of the link at `index` into the column of another table.
+    pub fn clone_row_into(&self, index: &Index<C>, dest: &mut Self, cx: &CloneContext) -> Index<C> {
+        dest.ids.push(self.cloned_link(index, cx))
+    }
+
+    fn cloned_link(&self, index: &Index<C>, cx: &CloneContext) -> Option<Id<ID>> {
+        let mut link = self.get(index).copied();
+        if cx.remaps_links() {
+            link.remap(cx);
+        }
+        link
+    }
+}
+
+impl<C, ID: Arena> Relation<ID> for IdColumn<C, ID> {
+    type Owner = C;
+
+    fn linked(&self, id: Id<ID>) -> Vec<usize> {
+        let linked = self.ids.iter().into_iter().enumerate();
+        linked
+            .filter(|(_, v)| **v == Some(id))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    fn relink(&mut self, from: Id<ID>, to: Id<ID>) {
+        self.replace_links(from, Some(to));
+    }
+}
+
+/// Unlinking leaves the column's generation alone, since `id` need not have been killed. The next
+/// `validate` catches up with the allocator.
+impl<C, ID: Arena> NullableRelation<ID> for IdColumn<C, ID> {
+    fn unlink(&mut self, id: Id<ID>) {
+        self.replace_links(id, None);
+    }
+}
+
+impl<C, ID: Arena> IdColumn<C, ID> {
+    fn replace_links(&mut self, from: Id<ID>, to: Option<Id<ID>>) {
+        for value in self
+            .ids
+            .iter_mut()
+            .into_iter()
+            .filter(|v| **v == Some(from))
+        {
+            *value = to;
+        }
+    }
+}
+
+impl<C, ID: Arena> MemoryUsage for IdColumn<C, ID> {
+    fn memory_usage(&self) -> usize {
+        self.ids.memory_usage()
+    }
+}
+
+impl<C, ID: Arena> StateHash for IdColumn<C, ID> {
+    fn state_hash(&self, hasher: &mut StateHasher) {
+        self.ids.state_hash(hasher);
+    }
+}
+
 impl<C, ID: Arena<Allocator = DynamicAllocator<ID>>> IdColumn<C, ID> {
     pub fn validate<'a>(&'a mut self, allocator: &'a Allocator<ID>) -> Valid<'a, &Self> {
         self.synchronize(allocator);
@@ -103,7 +174,7 @@ impl<C, ID: Arena<Allocator = DynamicAllocator<ID>>> IdColumn<C, ID> {
     }
 }
 
-impl<'a, C, ID> Valid<'a, &'a IdColumn<C, ID>> {
+impl<'a, C, ID: Arena> Valid<'a, &'a IdColumn<C, ID>> {
     pub fn iter(&self) -> Valid<'a, Iter<C, Option<Id<ID>>>> {
         Valid::new(self.value.iter())
     }
@@ -1,4 +1,4 @@
-use crate::ValidId;
+use crate::{Arena, MemoryUsage, ValidId};
 use std::marker::PhantomData;
 
 #[derive(Debug)]
@@ -16,19 +16,21 @@ impl<C, E> Default for IdIndices<C, E> {
     }
 }
 
-impl<C, E> IdIndices<C, E> {
+impl<C: Arena, E> IdIndices<C, E> {
     pub fn insert<I: ValidId<C>, IE: Into<E>>(&mut self, id: I, index: IE) {
         self.insert_inner(id.index(), index.into());
     }
 
     fn insert_inner(&mut self, index: usize, value: E) {
-        if let Some(v) = self.values.get_mut(index) {
-            *v = Some(value);
-        } else if self.values.len() == index {
-            self.values.push(Some(value));
-        } else {
-            panic!("Invalid index: {:?}", std::any::type_name::<Self>());
+        if self.values.len() <= index {
+            self.values.resize_with(index + 1, || None);
         }
+
+        self.values[index] = Some(value);
+    }
+
+    pub fn get<I: ValidId<C>>(&self, id: I) -> Option<&E> {
+        self.values.get(id.index())?.as_ref()
     }
 
     pub fn remove<I: ValidId<C>>(&mut self, id: I) -> Option<E> {
@@ -43,3 +45,9 @@ impl<C, E> IdIndices<C, E> {
         }
     }
 }
+
+impl<C, E> MemoryUsage for IdIndices<C, E> {
+    fn memory_usage(&self) -> usize {
+        self.values.capacity() * std::mem::size_of::<Option<E>>()
+    }
+}
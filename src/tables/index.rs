@@ -38,7 +38,7 @@ impl<C> std::hash::Hash for Index<C> {
 }
 
 impl<C> Index<C> {
-    pub(super) fn new(index: usize) -> Self {
+    pub(crate) fn new(index: usize) -> Self {
         Index(index as u32, PhantomData)
     }
 
@@ -38,6 +38,30 @@ macro_rules! table {
             }
         }
 
+        impl $crate::MemoryUsage for $table {
+            fn memory_usage(&self) -> usize {
+                $crate::MemoryUsage::memory_usage(&self.id)
+                $(
+                    + $crate::MemoryUsage::memory_usage(&self.$field)
+                )*
+                $(
+                    + $crate::MemoryUsage::memory_usage(&self.$link)
+                )*
+            }
+        }
+
+        impl $crate::StateHash for $table {
+            fn state_hash(&self, hasher: &mut $crate::StateHasher) {
+                $crate::StateHash::state_hash(&self.id, hasher);
+                $(
+                    $crate::StateHash::state_hash(&self.$field, hasher);
+                )*
+                $(
+                    $crate::StateHash::state_hash(&self.$link, hasher);
+                )*
+            }
+        }
+
         #[allow(dead_code)]
         impl $table {
             fn insert<'a>(
@@ -46,16 +70,20 @@ macro_rules! table {
                 indices: &mut $crate::IdIndices<$arena, $state_index>
             ) {
                 let id = $row::id(&row);
-                let index = self.insert_inner(row.value);
+                let index = self.insert_inner(row);
                 indices.insert(id, index);
             }
 
-            fn insert_inner(&mut self, row: $row) -> $crate::Index<Self> {
+            fn insert_inner(&mut self, row: Valid<'_, $row>) -> $crate::Index<Self> {
+                $(
+                    let $link = $row::$link(&row);
+                )*
+                let row = row.into_inner();
                 $(
                     self.$field.push(row.$field);
                 )*
                 $(
-                    self.$link.push(row.$link.map(Valid::assert));
+                    self.$link.push($link);
                 )*
                 self.id.push(row.id)
             }
@@ -66,7 +94,9 @@ macro_rules! table {
                 indices: &mut $crate::IdIndices<$arena, $state_index>
             ) -> $row {
                 let row = self.swap_remove_inner(&index);
-                if let Some(swapped) = self.id.get(&index).map($crate::Valid::assert) {
+                if let Some(swapped) = self.id.get(&index) {
+                    // SAFETY: only the index of the id is used, to update its entry in `indices`
+                    let swapped = unsafe { $crate::Valid::assume_valid(*swapped) };
                     indices.insert(swapped, index);
                 }
                 row
@@ -84,7 +114,47 @@ macro_rules! table {
                 }
             }
 
-            pub fn indices(&self) -> $crate::Indices<Self> {
+            /// Pushes a copy of the row at `index` for the clone of its entity, with links
+            /// pointed at their clones if the context remaps links.
+            pub fn clone_row(
+                &mut self,
+                index: &$crate::Index<Self>,
+                clone: $crate::Id<$arena>,
+                cx: &$crate::CloneContext,
+            ) -> $crate::Index<Self>
+            where
+                $( for<'x> $t: Clone, )*
+            {
+                $(
+                    self.$field.push(Clone::clone(self.$field.get(index).unwrap()));
+                )*
+                $(
+                    self.$link.clone_row(index, cx);
+                )*
+                self.id.push(clone)
+            }
+
+            /// Pushes a copy of the row at `index` into another table, see `clone_row`.
+            pub fn clone_row_into(
+                &self,
+                index: &$crate::Index<Self>,
+                dest: &mut Self,
+                clone: $crate::Id<$arena>,
+                cx: &$crate::CloneContext,
+            ) -> $crate::Index<Self>
+            where
+                $( for<'x> $t: Clone, )*
+            {
+                $(
+                    dest.$field.push(Clone::clone(self.$field.get(index).unwrap()));
+                )*
+                $(
+                    self.$link.clone_row_into(index, &mut dest.$link, cx);
+                )*
+                dest.id.push(clone)
+            }
+
+            pub fn indices(&self) -> $crate::Indices<'_, Self> {
                 self.id.indices()
             }
 
@@ -123,16 +193,19 @@ macro_rules! table {
                         $link: Some($link.id()),
                     )*
                 };
-                Valid::assert(value)
+                // SAFETY: the row borrows no longer than any of the ids it was built from
+                unsafe { $crate::Valid::assume_valid(value) }
             }
 
             pub fn id<'a>(row: &Valid<'a, $row>) -> Valid<'a, Id<$arena>> {
-                Valid::assert(row.value.id)
+                // SAFETY: the id is taken from the valid row
+                unsafe { row.map_ref(|row| row.id) }
             }
 
             $(
                 pub fn $link<'a>(row: &Valid<'a, $row>) -> Option<Valid<'a, Id<$a>>> {
-                    row.value.$link.map(Valid::assert)
+                    // SAFETY: the link is taken from the valid row
+                    row.value().$link.map(|id| unsafe { $crate::Valid::assume_valid(id) })
                 }
             )*
         }
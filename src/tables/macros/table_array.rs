@@ -41,14 +41,19 @@ macro_rules! table_array {
             )
              where $row_enum: From<R>
             {
-                self.insert_inner(id, row.value.into())
+                // SAFETY: converting a row into the enum keeps the ids that it contains
+                self.insert_inner(id, unsafe { row.map(Into::into) })
             }
 
-            fn insert_inner<I: $crate::ValidId<$arena>>(&mut self, id: I, row: $row_enum) {
+            fn insert_inner<I: $crate::ValidId<$arena>>(&mut self, id: I, row: Valid<'_, $row_enum>) {
                 self.remove(id);
-                match row {
+                match row.into_inner() {
                     $(
-                        $row_enum::$variant(row) => self.$field.insert(Valid::assert(row), &mut self.indices),
+                        $row_enum::$variant(row) => {
+                            // SAFETY: the variant is as valid as the enum that contained it
+                            let row = unsafe { $crate::Valid::assume_valid(row) };
+                            self.$field.insert(row, &mut self.indices)
+                        }
                     )*
                 };
             }
@@ -66,6 +71,78 @@ macro_rules! table_array {
             }
         }
 
+        impl $crate::CloneRows<$arena> for $table
+        where
+            $( $( for<'x> $v_t: Clone, )* )*
+        {
+            fn clone_rows(
+                &mut self,
+                clones: &[($crate::Id<$arena>, $crate::Id<$arena>)],
+                cx: &$crate::CloneContext,
+            ) {
+                for (src, clone) in clones {
+                    // SAFETY: both entities are alive, the clone having just been created
+                    let (src, clone) = unsafe {
+                        ($crate::Valid::assume_valid(*src), $crate::Valid::assume_valid(*clone))
+                    };
+
+                    let index: $index_enum = match self.indices.get(src) {
+                        $(
+                            Some($index_enum::$variant(index)) => {
+                                self.$field.clone_row(index, $crate::ValidId::id(clone), cx).into()
+                            }
+                        )*
+                        None => continue,
+                    };
+                    self.indices.insert(clone, index);
+                }
+            }
+
+            fn clone_rows_into(
+                &self,
+                dest: &mut Self,
+                clones: &[($crate::Id<$arena>, $crate::Id<$arena>)],
+                cx: &$crate::CloneContext,
+            ) {
+                for (src, clone) in clones {
+                    // SAFETY: the source is alive in `self` and its clone in `dest`
+                    let (src, clone) = unsafe {
+                        ($crate::Valid::assume_valid(*src), $crate::Valid::assume_valid(*clone))
+                    };
+
+                    let index: $index_enum = match self.indices.get(src) {
+                        $(
+                            Some($index_enum::$variant(index)) => {
+                                self.$field
+                                    .clone_row_into(index, &mut dest.$field, $crate::ValidId::id(clone), cx)
+                                    .into()
+                            }
+                        )*
+                        None => continue,
+                    };
+                    dest.indices.insert(clone, index);
+                }
+            }
+        }
+
+        impl $crate::MemoryUsage for $table {
+            fn memory_usage(&self) -> usize {
+                $crate::MemoryUsage::memory_usage(&self.indices)
+                $(
+                    + $crate::MemoryUsage::memory_usage(&self.$field)
+                )*
+            }
+        }
+
+        // the indices are not hashed, since they are determined by the ids in each table
+        impl $crate::StateHash for $table {
+            fn state_hash(&self, hasher: &mut $crate::StateHasher) {
+                $(
+                    $crate::StateHash::state_hash(&self.$field, hasher);
+                )*
+            }
+        }
+
         row_enum! { enum $row_enum { $( $variant($v_row), )* } }
         index_enum! { enum $index_enum { $( $variant, )* } }
 
@@ -13,6 +13,12 @@ fixed_arena!(Freighter);
 #[derive(Debug, Copy, Clone, PartialOrd, PartialEq)]
 pub struct Time(f64);
 
+impl StateHash for Time {
+    fn state_hash(&self, hasher: &mut StateHasher) {
+        self.0.state_hash(hasher);
+    }
+}
+
 table_array! {
     struct FreighterState {
         type Arena = Freighter;
@@ -57,6 +63,103 @@ fn test() {
     // panic!("{:#?}", s);
 }
 
+#[test]
+fn memory_usage() {
+    let mut a = Allocator::<Freighter>::default();
+    let mut colonies = Allocator::<Colony>::default();
+    let mut s = FreighterState::default();
+    assert_eq!(0, s.memory_usage());
+
+    let c = colonies.create();
+    let id = a.create();
+    s.insert(id, IdleRow::new(id, Time(0.0), c));
+
+    assert_eq!(
+        s.indices.memory_usage() + s.idle.memory_usage(),
+        s.memory_usage()
+    );
+    assert!(s.idle.memory_usage() >= std::mem::size_of::<Time>());
+}
+
+#[test]
+fn state_hash() {
+    let mut a = Allocator::<Freighter>::default();
+    let mut colonies = Allocator::<Colony>::default();
+    let mut lhs = FreighterState::default();
+    let mut rhs = FreighterState::default();
+
+    let c = colonies.create();
+    let id = a.create();
+    lhs.insert(id, IdleRow::new(id, Time(0.0), c));
+    rhs.insert(id, IdleRow::new(id, Time(0.0), c));
+
+    assert_eq!(lhs.state_digest(), rhs.state_digest());
+
+    rhs.insert(id, MovingRow::new(id, c, c));
+
+    assert_ne!(lhs.state_digest(), rhs.state_digest());
+    assert_eq!(lhs.moving.state_digest(), Moving::default().state_digest());
+}
+
+#[derive(Debug, Default)]
+pub struct Tanker {
+    alloc: Allocator<Self>,
+    state: TankerState,
+}
+
+fixed_arena!(Tanker);
+
+table_array! {
+    struct TankerState {
+        type Arena = Tanker;
+        type RowEnum = enum TankerStateRow;
+        type IndexEnum = enum TankerStateIndex;
+        tables {
+            docked: struct Docked {
+                type Row = struct DockedRow;
+                fields {
+                    arrival: Time,
+                }
+                links {
+                    port: Colony,
+                }
+            },
+        }
+    }
+}
+
+clone_entity! {
+    Tanker {
+        alloc: alloc,
+        components: [],
+        links: [],
+        rows: [],
+        tables: [state],
+    }
+}
+
+#[test]
+fn clone_entity_copies_table_rows() {
+    let mut colonies = Allocator::<Colony>::default();
+    let mut tanker = Tanker::default();
+
+    let port = colonies.create();
+    let a = tanker.alloc.create();
+    tanker.alloc.create();
+    tanker.state.insert(a, DockedRow::new(a, Time(1.0), port));
+
+    let clone = tanker.clone_entity(a).unwrap();
+
+    let index = match tanker.state.indices.get(clone) {
+        Some(TankerStateIndex::Docked(index)) => *index,
+        None => panic!("the clone has no row"),
+    };
+    assert_eq!(2, tanker.state.docked.len());
+    assert_eq!(Some(&clone), tanker.state.docked.id.get(&index));
+    assert_eq!(Some(&Time(1.0)), tanker.state.docked.arrival.get(&index));
+    assert_eq!(Some(&port.id()), tanker.state.docked.port.get(&index));
+}
+
 #[derive(Debug, Default)]
 pub struct ArenaA;
 dynamic_arena!(ArenaA);
@@ -94,7 +197,8 @@ impl TableC {
         allocator_a: &'a Allocator<ArenaA>,
         allocator_b: &'a Allocator<ArenaB>,
     ) -> Option<RowC<Valid<'a, Id<ArenaC>>, Valid<'a, Id<ArenaA>>, Valid<'a, Id<ArenaB>>>> {
-        let id = Valid::assert(self.ids.swap_remove(index).unwrap());
+        // SAFETY: ids are removed from the table before they are killed
+        let id = unsafe { Valid::assume_valid(self.ids.swap_remove(index).unwrap()) };
         let value = self.value.swap_remove(index);
 
         let id_a = self
@@ -1,17 +1,77 @@
 use crate::*;
 
-pub trait ValidId<A>: Copy {
+/// An id that is known to refer to a living entity of arena `A`, so it can index components
+/// without a check.
+///
+/// The trait is sealed: it is implemented for fixed ids, for `Valid` ids, and for sub-arena ids
+/// by `sub_arena!`.
+pub trait ValidId<A: Arena>: Copy + __private::Sealed<A> {
     fn index(self) -> usize;
     fn id(self) -> Id<A>;
 }
 
-pub trait ValidEdge<A> {
+pub trait ValidEdge<A: Arena> {
     type Id: ValidId<A>;
     fn edge(&self) -> Edge<A>;
     fn from(&self) -> Self::Id;
     fn to(&self) -> Self::Id;
 }
 
+#[doc(hidden)]
+pub mod __private {
+    /// Seals `ValidId`. Not part of the public API.
+    ///
+    /// # Safety
+    ///
+    /// Implementors must only produce ids of living entities of arena `A`.
+    pub unsafe trait Sealed<A> {}
+}
+
+/// Checks whether an id refers to a living entity, for both fixed and dynamic allocators.
+pub trait Validate<A: Arena> {
+    fn validate(&self, id: Id<A>) -> Option<Valid<'_, Id<A>>>;
+}
+
+/// A value that optionally links to an entity in arena `A`, such as `Id<A>` or `Option<Id<A>>`.
+pub trait LinkId<A: Arena> {
+    fn link_id(self) -> Option<Id<A>>;
+}
+
+/// Storage that refers to entities of arena `A` and must forget them when they are killed.
+pub trait OnKill<A: Arena> {
+    fn on_kill(&mut self, id: Id<A>);
+}
+
+/// Storage of links from the entities or rows of `Owner` to entities of arena `B`, see
+/// [`World::kill_linked`].
+pub trait Relation<B: Arena> {
+    type Owner;
+
+    /// The indices of the entries that link to `id`.
+    fn linked(&self, id: Id<B>) -> Vec<usize>;
+
+    /// Replaces every link to `from` with a link to `to`.
+    fn relink(&mut self, from: Id<B>, to: Id<B>);
+}
+
+/// A [`Relation`] whose links are optional.
+pub trait NullableRelation<B: Arena>: Relation<B> {
+    /// Removes every link to `id`.
+    fn unlink(&mut self, id: Id<B>);
+}
+
+/// The number of bytes of heap memory owned by a collection, including unused capacity.
+///
+/// Heap memory owned by the values themselves, such as the contents of a `String`, is not included.
+pub trait MemoryUsage {
+    fn memory_usage(&self) -> usize;
+}
+
+/// An estimate of the heap memory used by a B-tree map, assuming that nodes are two-thirds full.
+pub(crate) fn btree_map_usage<K, V>(map: &std::collections::BTreeMap<K, V>) -> usize {
+    map.len() * std::mem::size_of::<(K, V)>() * 3 / 2
+}
+
 #[cfg(test)]
 mod test {
     use crate::allocator::test::GenerationalArena;
@@ -21,8 +81,9 @@ mod test {
     struct Thing(Id<GenerationalArena>);
 
     impl Thing {
-        pub fn new<'a>(id: impl ValidId<GenerationalArena> + 'a) -> Valid<'a, Self> {
-            Valid::assert(Self(id.id()))
+        pub fn new(id: Valid<Id<GenerationalArena>>) -> Valid<Self> {
+            // SAFETY: the id is wrapped unchanged
+            unsafe { id.map(Self) }
         }
     }
 
@@ -36,7 +97,6 @@ mod test {
 
         let _new_valid = a.create();
 
-        // uncommenting should break compilation
-        // dbg!(thing);
+        // using `_thing` here fails, see tests/compile_fail/derived_valid_outlives_kill.rs
     }
 }
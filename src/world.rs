@@ -0,0 +1,372 @@
+use crate::*;
+use std::any::{Any, TypeId};
+use std::fmt::{Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard, TryLockError};
+
+/// A container for arenas and other resources, keyed by type.
+///
+/// Resources are borrowed through `&World`, so several arenas can be borrowed mutably at once.
+/// Borrowing the same resource mutably twice is an error rather than a deadlock.
+#[derive(Default)]
+pub struct World {
+    resources: HashMap<TypeId, Resource>,
+    hooks: HashMap<TypeId, Vec<Hook>>,
+}
+
+struct Resource {
+    name: &'static str,
+    value: RwLock<Box<dyn Any + Send + Sync>>,
+}
+
+type Hook = Box<dyn Fn(&World, AnyId) -> Result<(), BorrowError> + Send + Sync>;
+
+impl World {
+    /// Adds a resource, replacing any previous value of the same type.
+    pub fn insert<T: Send + Sync + 'static>(&mut self, value: T) {
+        let resource = Resource {
+            name: std::any::type_name::<T>(),
+            value: RwLock::new(Box::new(value)),
+        };
+
+        self.resources.insert(TypeId::of::<T>(), resource);
+    }
+
+    /// Panics if a borrow of the resource panicked, see [`BorrowError::Poisoned`].
+    pub fn remove<T: Send + Sync + 'static>(&mut self) -> Option<T> {
+        let resource = self.resources.remove(&TypeId::of::<T>())?;
+        let value = match resource.value.into_inner() {
+            Ok(value) => value,
+            Err(_) => panic!("{}", BorrowError::Poisoned(resource.name)),
+        };
+
+        value.downcast().ok().map(|value| *value)
+    }
+
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.resources.contains_key(&TypeId::of::<T>())
+    }
+
+    /// Panics if the resource cannot be borrowed, see [`World::try_get`].
+    pub fn get<T: 'static>(&self) -> Res<'_, T> {
+        self.try_get().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Panics if the resource cannot be borrowed, see [`World::try_get_mut`].
+    pub fn get_mut<T: 'static>(&self) -> ResMut<'_, T> {
+        self.try_get_mut().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_get<T: 'static>(&self) -> Result<Res<'_, T>, BorrowError> {
+        let resource = self.resource::<T>()?;
+
+        let guard = match resource.value.try_read() {
+            Ok(guard) => guard,
+            Err(TryLockError::Poisoned(_)) => return Err(BorrowError::Poisoned(resource.name)),
+            Err(TryLockError::WouldBlock) => return Err(BorrowError::Conflict(resource.name)),
+        };
+
+        Ok(Res {
+            guard,
+            marker: PhantomData,
+        })
+    }
+
+    pub fn try_get_mut<T: 'static>(&self) -> Result<ResMut<'_, T>, BorrowError> {
+        let resource = self.resource::<T>()?;
+
+        let guard = match resource.value.try_write() {
+            Ok(guard) => guard,
+            Err(TryLockError::Poisoned(_)) => return Err(BorrowError::Poisoned(resource.name)),
+            Err(TryLockError::WouldBlock) => return Err(BorrowError::Conflict(resource.name)),
+        };
+
+        Ok(ResMut {
+            guard,
+            marker: PhantomData,
+        })
+    }
+
+    fn resource<T: 'static>(&self) -> Result<&Resource, BorrowError> {
+        self.resources
+            .get(&TypeId::of::<T>())
+            .ok_or_else(|| BorrowError::Missing(std::any::type_name::<T>()))
+    }
+
+    /// Runs `hook` after an entity of arena `A` is killed through [`World::kill`]. An error
+    /// returned by the hook is returned from `kill`.
+    pub fn on_kill<A, F>(&mut self, hook: F)
+    where
+        A: Arena + 'static,
+        F: Fn(&World, Id<A>) -> Result<(), BorrowError> + Send + Sync + 'static,
+    {
+        let hook = move |world: &World, id: AnyId| match id.downcast::<A>() {
+            Some(id) => hook(world, id),
+            None => Ok(()),
+        };
+
+        self.hooks
+            .entry(TypeId::of::<A>())
+            .or_default()
+            .push(Box::new(hook));
+    }
+
+    /// Removes killed entities of arena `A` from storage `D` within resource `S`.
+    ///
+    /// ```ignore
+    /// world.cascade::<Colony, _, _, _>(|govt: &mut Government| &mut govt.capital_map);
+    /// ```
+    pub fn cascade<A, S, D, F>(&mut self, storage: F)
+    where
+        A: Arena + 'static,
+        S: Send + Sync + 'static,
+        D: OnKill<A>,
+        F: Fn(&mut S) -> &mut D + Send + Sync + 'static,
+    {
+        self.on_kill::<A, _>(move |world, id| {
+            let mut resource = world.try_get_mut::<S>()?;
+            storage(&mut resource).on_kill(id);
+            Ok(())
+        });
+    }
+
+    /// Kills an entity and runs the kill hooks of its arena. Returns false if it was already dead.
+    ///
+    /// Returns an error if the arena, or a resource that a hook needs, cannot be borrowed, such as
+    /// when it is missing or borrowed by the caller. The hooks stop at the first error, after the
+    /// entity has been killed.
+    pub fn kill<A>(&self, id: Id<A>) -> Result<bool, BorrowError>
+    where
+        A: HasAllocator + Arena<Allocator = DynamicAllocator<A>> + 'static,
+    {
+        let killed = self.try_get_mut::<A>()?.allocator_mut().kill(id);
+
+        if killed {
+            for hook in self.hooks.get(&TypeId::of::<A>()).into_iter().flatten() {
+                hook(self, AnyId::new(id))?;
+            }
+        }
+
+        Ok(killed)
+    }
+}
+
+impl Debug for World {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_set()
+            .entries(self.resources.values().map(|resource| resource.name))
+            .finish()
+    }
+}
+
+/// A shared borrow of a resource, see [`World::get`].
+pub struct Res<'a, T> {
+    guard: RwLockReadGuard<'a, Box<dyn Any + Send + Sync>>,
+    marker: PhantomData<&'a T>,
+}
+
+impl<T: 'static> Deref for Res<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.guard.downcast_ref().unwrap()
+    }
+}
+
+/// A mutable borrow of a resource, see [`World::get_mut`].
+pub struct ResMut<'a, T> {
+    guard: RwLockWriteGuard<'a, Box<dyn Any + Send + Sync>>,
+    marker: PhantomData<&'a mut T>,
+}
+
+impl<T: 'static> Deref for ResMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.guard.downcast_ref().unwrap()
+    }
+}
+
+impl<T: 'static> DerefMut for ResMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.guard.downcast_mut().unwrap()
+    }
+}
+
+/// The reason a resource could not be borrowed from a [`World`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BorrowError {
+    /// No resource of the named type has been inserted.
+    Missing(&'static str),
+    /// The named resource is already borrowed in a conflicting way.
+    Conflict(&'static str),
+    /// A borrow of the named resource panicked, so its value may be only partly updated.
+    Poisoned(&'static str),
+}
+
+impl Display for BorrowError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BorrowError::Missing(name) => write!(f, "{} is not in the world", name),
+            BorrowError::Conflict(name) => write!(f, "{} is already borrowed", name),
+            BorrowError::Poisoned(name) => write!(f, "{} was poisoned by a panic", name),
+        }
+    }
+}
+
+impl std::error::Error for BorrowError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct Colony {
+        alloc: Allocator<Self>,
+        population: Component<Self, f64>,
+        exports: Graph<Colony, f64>,
+    }
+
+    dynamic_arena!(Colony);
+
+    impl HasAllocator for Colony {
+        fn allocator(&self) -> &Allocator<Self> {
+            &self.alloc
+        }
+
+        fn allocator_mut(&mut self) -> &mut Allocator<Self> {
+            &mut self.alloc
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct Government {
+        alloc: Allocator<Self>,
+        capital: IdLink<Self, Colony>,
+        tariffs: IdMap<Colony, f64>,
+    }
+
+    fixed_arena!(Government);
+
+    #[derive(Debug, Default, PartialEq)]
+    struct Turn(u32);
+
+    fn world() -> (World, Id<Colony>, Id<Colony>) {
+        let mut colony = Colony::default();
+        let mut govt = Government::default();
+
+        let a = colony.alloc.create().value;
+        let b = colony.alloc.create().value;
+        let a_valid = colony.alloc.validate(a).unwrap();
+        let b_valid = colony.alloc.validate(b).unwrap();
+        colony
+            .exports
+            .insert(Edge::new_valid(a_valid, b_valid), 2.0);
+
+        let g = govt.alloc.create();
+        govt.capital.insert(g, Some(a_valid));
+        govt.tariffs.insert(b_valid, 0.1);
+
+        let mut world = World::default();
+        world.insert(colony);
+        world.insert(govt);
+        world.insert(Turn(0));
+
+        (world, a, b)
+    }
+
+    #[test]
+    fn borrow_several_resources_mutably() {
+        let (world, a, _) = world();
+
+        let mut colony = world.get_mut::<Colony>();
+        let mut turn = world.get_mut::<Turn>();
+
+        let colony = &mut *colony;
+        colony
+            .population
+            .insert(colony.alloc.validate(a).unwrap(), 5.0);
+        turn.0 += 1;
+
+        assert_eq!(Turn(1), *turn);
+    }
+
+    #[test]
+    fn conflicting_borrow_is_an_error() {
+        let (world, _, _) = world();
+
+        let _colony = world.get::<Colony>();
+
+        assert!(world.try_get::<Colony>().is_ok());
+        assert_eq!(
+            BorrowError::Conflict(std::any::type_name::<Colony>()),
+            world.try_get_mut::<Colony>().err().unwrap()
+        );
+        assert_eq!(
+            BorrowError::Missing("f64"),
+            world.try_get::<f64>().err().unwrap()
+        );
+    }
+
+    #[test]
+    fn kill_cascades_to_dependent_storage() {
+        let (mut world, a, b) = world();
+        world.cascade::<Colony, _, _, _>(|govt: &mut Government| &mut govt.capital);
+        world.cascade::<Colony, _, _, _>(|govt: &mut Government| &mut govt.tariffs);
+        world.cascade::<Colony, _, _, _>(|colony: &mut Colony| &mut colony.exports);
+
+        assert_eq!(Ok(true), world.kill(a));
+        assert_eq!(Ok(true), world.kill(b));
+        assert_eq!(Ok(false), world.kill(a));
+
+        let govt = world.get::<Government>();
+        let g = govt.alloc.ids().next().unwrap();
+        assert_eq!(&None, govt.capital.get(g));
+        assert!(govt.tariffs.iter().next().is_none());
+        assert!(world.get_mut::<Colony>().exports.remove_ids(a, b).is_none());
+    }
+
+    #[test]
+    fn kill_returns_error_if_hook_storage_is_borrowed() {
+        let (mut world, a, _) = world();
+        world.cascade::<Colony, _, _, _>(|govt: &mut Government| &mut govt.tariffs);
+
+        let _govt = world.get::<Government>();
+
+        assert_eq!(
+            Err(BorrowError::Conflict(std::any::type_name::<Government>())),
+            world.kill(a)
+        );
+        assert!(!world.get::<Colony>().alloc.is_alive(a));
+    }
+
+    #[test]
+    fn poisoned_resource_is_an_error() {
+        let (mut world, _, _) = world();
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _turn = world.get_mut::<Turn>();
+            panic!("turn failed");
+        }));
+        assert!(result.is_err());
+
+        let poisoned = BorrowError::Poisoned(std::any::type_name::<Turn>());
+        assert_eq!(Some(poisoned), world.try_get::<Turn>().err());
+        assert_eq!(Some(poisoned), world.try_get_mut::<Turn>().err());
+
+        let removed = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            world.remove::<Turn>();
+        }));
+        assert!(removed.is_err());
+    }
+
+    #[test]
+    fn remove_returns_resource() {
+        let (mut world, _, _) = world();
+
+        assert_eq!(Some(Turn(0)), world.remove::<Turn>());
+        assert!(!world.contains::<Turn>());
+        assert_eq!(None, world.remove::<Turn>());
+    }
+}
@@ -0,0 +1,8 @@
+// The expected .stderr files are rustc diagnostics, which change between releases. They are
+// checked on the release they were generated with, and regenerated with TRYBUILD=overwrite.
+#[rustversion::attr(not(stable(1.95)), ignore)]
+#[test]
+fn compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/compile_fail/*.rs");
+}
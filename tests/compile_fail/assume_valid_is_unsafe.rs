@@ -0,0 +1,15 @@
+use gen_id::*;
+
+#[derive(Debug)]
+struct Ship;
+
+dynamic_arena!(Ship);
+
+fn main() {
+    let mut alloc = Allocator::<Ship>::default();
+    let id = alloc.create().id();
+    alloc.kill(id);
+
+    let valid: Valid<Id<Ship>> = Valid::assume_valid(id);
+    println!("{:?}", valid);
+}
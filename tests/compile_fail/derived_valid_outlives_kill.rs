@@ -0,0 +1,18 @@
+use gen_id::*;
+
+#[derive(Debug)]
+struct Ship;
+
+dynamic_arena!(Ship);
+
+fn main() {
+    let mut alloc = Allocator::<Ship>::default();
+
+    let from = alloc.create().id();
+    let to = alloc.create().id();
+
+    let route = Edge::new_valid(alloc.validate(from).unwrap(), alloc.validate(to).unwrap());
+    alloc.kill(to);
+
+    println!("{:?}", route);
+}
@@ -0,0 +1,33 @@
+use gen_id::*;
+
+#[derive(Debug, Default)]
+struct Ship {
+    alloc: Allocator<Self>,
+    name: Component<Self, &'static str>,
+}
+
+dynamic_arena!(Ship);
+
+impl HasAllocator for Ship {
+    fn allocator(&self) -> &Allocator<Self> {
+        &self.alloc
+    }
+
+    fn allocator_mut(&mut self) -> &mut Allocator<Self> {
+        &mut self.alloc
+    }
+}
+
+fn main() {
+    let mut ship = Ship::default();
+    let id = ship.alloc.create().id();
+    ship.name.insert(ship.alloc.validate(id).unwrap(), "Alpha");
+
+    let entity = ship.entity_mut(id).unwrap();
+    let name = entity.field_mut(|ship| {
+        ship.alloc.kill(id);
+        (&ship.alloc, &mut ship.name)
+    });
+
+    println!("{:?} {:?}", name, entity.id());
+}
@@ -0,0 +1,29 @@
+use gen_id::*;
+
+#[derive(Debug)]
+struct Ship;
+
+dynamic_arena!(Ship);
+
+#[derive(Debug, Copy, Clone)]
+struct Forged(Id<Ship>);
+
+impl ValidId<Ship> for Forged {
+    fn index(self) -> usize {
+        0
+    }
+
+    fn id(self) -> Id<Ship> {
+        self.0
+    }
+}
+
+fn main() {
+    let mut alloc = Allocator::<Ship>::default();
+    let speed = Component::<Ship, f64>::default();
+
+    let id = alloc.create().id();
+    alloc.kill(id);
+
+    println!("{}", speed.get(Forged(id)));
+}
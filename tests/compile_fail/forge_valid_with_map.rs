@@ -0,0 +1,17 @@
+use gen_id::*;
+
+#[derive(Debug)]
+struct Ship;
+
+dynamic_arena!(Ship);
+
+fn main() {
+    let mut alloc = Allocator::<Ship>::default();
+    let living = alloc.create().id();
+    let dead = alloc.create().id();
+    alloc.kill(dead);
+
+    let forged = alloc.validate(living).unwrap().map(|_| dead);
+
+    println!("{:?}", forged);
+}
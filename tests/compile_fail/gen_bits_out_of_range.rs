@@ -0,0 +1,13 @@
+use gen_id::*;
+use std::num::NonZeroU32;
+
+#[derive(Debug)]
+struct Ship;
+
+dynamic_arena!(Ship, Layout<NonZeroU32, 32>);
+
+fn main() {
+    let mut alloc = Allocator::<Ship>::default();
+    let id = alloc.create().id();
+    println!("{:?}", id);
+}
@@ -0,0 +1,20 @@
+use gen_id::*;
+
+#[derive(Debug)]
+struct Planet;
+
+fixed_arena!(Planet);
+
+#[derive(Debug)]
+struct Moon;
+
+fixed_arena!(Moon);
+
+fn main() {
+    let mut moons = Allocator::<Moon>::default();
+    let mut mass = Component::<Planet, f64>::default();
+
+    let moon = moons.create();
+
+    println!("{}", mass.get(moon));
+}
@@ -0,0 +1,15 @@
+use gen_id::*;
+
+#[derive(Debug)]
+struct Ship;
+
+dynamic_arena!(Ship);
+
+fn same_allocator<'id>(_: ValidToken<'id, Ship>, _: ValidToken<'id, Ship>) {}
+
+fn main() {
+    let home = Allocator::<Ship>::default();
+    let away = Allocator::<Ship>::default();
+
+    home.with_token(|home| away.with_token(|away| same_allocator(home, away)));
+}
@@ -0,0 +1,17 @@
+use gen_id::*;
+
+#[derive(Debug)]
+struct Ship;
+
+dynamic_arena!(Ship);
+
+fn main() {
+    let mut alloc = Allocator::<Ship>::default();
+    let mut speed = Component::<Ship, f64>::default();
+
+    let id = alloc.create();
+    speed.insert(id, 1.0);
+    let id = id.id();
+
+    println!("{}", speed.get(id));
+}
@@ -0,0 +1,15 @@
+use gen_id::*;
+
+#[derive(Debug)]
+struct Ship;
+
+dynamic_arena!(Ship);
+
+fn main() {
+    let mut alloc = Allocator::<Ship>::default();
+
+    let first = alloc.create();
+    let second = alloc.create();
+
+    println!("{:?} {:?}", first, second);
+}
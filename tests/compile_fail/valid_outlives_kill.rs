@@ -0,0 +1,16 @@
+use gen_id::*;
+
+#[derive(Debug)]
+struct Ship;
+
+dynamic_arena!(Ship);
+
+fn main() {
+    let mut alloc = Allocator::<Ship>::default();
+    let id = alloc.create().id();
+
+    let valid = alloc.validate(id).unwrap();
+    alloc.kill(id);
+
+    println!("{:?}", valid);
+}